@@ -0,0 +1,85 @@
+#ifndef FLOAT_HASH_OF_H
+#define FLOAT_HASH_OF_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+#define FLOAT_HASH_OF_FFI
+
+#if defined(FLOAT_HASH_OF_FFI)
+// The result of checking a double. Anything but `FLOAT_HASH_OF_STATUS_VALID` is the reason it is not a hash
+typedef enum FloatHashOfStatus {
+#if defined(FLOAT_HASH_OF_FFI)
+  FLOAT_HASH_OF_STATUS_VALID = 0,
+#endif
+#if defined(FLOAT_HASH_OF_FFI)
+  FLOAT_HASH_OF_STATUS_NAN,
+#endif
+#if defined(FLOAT_HASH_OF_FFI)
+  FLOAT_HASH_OF_STATUS_ZERO,
+#endif
+#if defined(FLOAT_HASH_OF_FFI)
+  FLOAT_HASH_OF_STATUS_INFINITE,
+#endif
+#if defined(FLOAT_HASH_OF_FFI)
+  FLOAT_HASH_OF_STATUS_SUBNORMAL,
+#endif
+#if defined(FLOAT_HASH_OF_FFI)
+  FLOAT_HASH_OF_STATUS_EXPONENT,
+#endif
+} FloatHashOfStatus;
+#endif
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+#if defined(FLOAT_HASH_OF_FFI)
+// `FloatHashOf::<String>::from(text)`, or 0 if the bytes are not UTF-8.
+// Uses Rust's default hasher, so only matches Rust built with the same compiler release.
+//
+// # Safety
+// `ptr` must point to `len` readable bytes, or `len` must be 0
+double float_hash_of_hash_utf8(const uint8_t *ptr, size_t len);
+#endif
+
+#if defined(FLOAT_HASH_OF_FFI)
+// `StableFloatHashOf::<String>::from(text)`, or 0 if the bytes are not UTF-8
+//
+// # Safety
+// `ptr` must point to `len` readable bytes, or `len` must be 0
+double float_hash_of_hash_utf8_stable(const uint8_t *ptr, size_t len);
+#endif
+
+#if defined(FLOAT_HASH_OF_FFI)
+// Whether `value` could have come from any `FloatHashOf`, and if not, why
+enum FloatHashOfStatus float_hash_of_validate(double value);
+#endif
+
+#if defined(FLOAT_HASH_OF_FFI)
+// `hash_u64_to_f64`
+double float_hash_of_from_u64(uint64_t hash);
+#endif
+
+#if defined(FLOAT_HASH_OF_FFI)
+// `hash_u64_to_f64_lossless`
+double float_hash_of_from_u64_lossless(uint64_t hash);
+#endif
+
+#if defined(FLOAT_HASH_OF_FFI)
+// `f64_to_hash_u64`. Writes to `out` only when `value` is valid
+//
+// # Safety
+// `out` must be valid for a write
+enum FloatHashOfStatus float_hash_of_to_u64(double value, uint64_t *out);
+#endif
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* FLOAT_HASH_OF_H */
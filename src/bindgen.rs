@@ -0,0 +1,146 @@
+use crate::{FloatHashOf, NullableFloatHash};
+use std::convert::TryFrom;
+use std::hash::BuildHasher;
+use wasm_bindgen::convert::{
+    FromWasmAbi, IntoWasmAbi, OptionFromWasmAbi, OptionIntoWasmAbi, RefFromWasmAbi,
+};
+use wasm_bindgen::describe::{inform, WasmDescribe, NAMED_EXTERNREF};
+use wasm_bindgen::JsValue;
+
+// FloatHashOf crosses as a JavaScript value rather than as an f64, because wasm-bindgen passes
+// Option<f64> as a flag and a number, which a custom type cannot reuse. A JavaScript value has a
+// single word Option, 0 for undefined, the same as an imported type such as js_sys::Number.
+// It is named "number", so TypeScript sees `number` and `number | undefined`.
+impl<T: ?Sized, S: BuildHasher> WasmDescribe for FloatHashOf<T, S> {
+    fn describe() {
+        inform(NAMED_EXTERNREF);
+        inform(6);
+        inform('n' as u32);
+        inform('u' as u32);
+        inform('m' as u32);
+        inform('b' as u32);
+        inform('e' as u32);
+        inform('r' as u32);
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> IntoWasmAbi for FloatHashOf<T, S> {
+    type Abi = <JsValue as IntoWasmAbi>::Abi;
+
+    #[inline]
+    fn into_abi(self) -> Self::Abi {
+        JsValue::from(self).into_abi()
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> OptionIntoWasmAbi for FloatHashOf<T, S> {
+    /// `undefined` in JavaScript
+    #[inline]
+    fn none() -> Self::Abi {
+        0
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> FromWasmAbi for FloatHashOf<T, S> {
+    type Abi = <JsValue as FromWasmAbi>::Abi;
+
+    /// Throws a JavaScript exception if `js` is not a number this crate could have produced
+    #[inline]
+    unsafe fn from_abi(js: Self::Abi) -> Self {
+        let value = JsValue::from_abi(js);
+        match value.as_f64().map(FloatHashOf::try_from) {
+            Some(Ok(hash)) => hash,
+            Some(Err(invalid)) => wasm_bindgen::throw_str(&invalid.to_string()),
+            None => wasm_bindgen::throw_str("expected a number for a float hash"),
+        }
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> OptionFromWasmAbi for FloatHashOf<T, S> {
+    /// `undefined` and `null` arrive as 0. The number 0, which is never a hash, is also `None`.
+    /// `from_abi` is not called for `None`, so the value is released here instead.
+    #[inline]
+    fn is_none(js: &Self::Abi) -> bool {
+        if *js == 0 {
+            return true;
+        }
+        let is_zero = unsafe { JsValue::ref_from_abi(*js) }.as_f64() == Some(0.);
+        if is_zero {
+            drop(unsafe { JsValue::from_abi(*js) });
+        }
+        is_zero
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> From<FloatHashOf<T, S>> for JsValue {
+    fn from(hash: FloatHashOf<T, S>) -> Self {
+        JsValue::from_f64(hash.into_inner())
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> WasmDescribe for NullableFloatHash<T, S> {
+    fn describe() {
+        f64::describe()
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> IntoWasmAbi for NullableFloatHash<T, S> {
+    type Abi = f64;
+
+    #[inline]
+    fn into_abi(self) -> f64 {
+        self.into_inner()
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> FromWasmAbi for NullableFloatHash<T, S> {
+    type Abi = f64;
+
+    /// Throws a JavaScript exception if `js` is neither +-0 nor a float hash
+    #[inline]
+    unsafe fn from_abi(js: f64) -> Self {
+        match NullableFloatHash::try_from(js) {
+            Ok(nullable) => nullable,
+            Err(invalid) => wasm_bindgen::throw_str(&invalid.to_string()),
+        }
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> From<NullableFloatHash<T, S>> for JsValue {
+    fn from(nullable: NullableFloatHash<T, S>) -> Self {
+        JsValue::from_f64(nullable.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type H = FloatHashOf<String>;
+
+    #[test]
+    fn none_is_undefined() {
+        assert_eq!(None::<H>.into_abi(), 0);
+        assert!(H::is_none(&0));
+        assert_eq!(unsafe { Option::<H>::from_abi(0) }, None);
+    }
+
+    #[test]
+    fn nullable_abi() {
+        type N = NullableFloatHash<String>;
+        let hash = H::from("test");
+        assert_eq!(N::from(hash).into_abi(), hash.into_inner());
+        assert_eq!(N::NULL.into_abi().to_bits(), 0);
+        assert_eq!(unsafe { N::from_abi(-0.) }, N::NULL);
+        assert_eq!(unsafe { N::from_abi(hash.into_inner()) }, N::from(hash));
+    }
+
+    #[test]
+    fn max_safe_integer_is_some() {
+        type N = NullableFloatHash<String>;
+        let hash = H::try_from(9_007_199_254_740_991.).unwrap();
+        let js = N::from(hash).into_abi();
+        assert_eq!(js, 9_007_199_254_740_991.);
+        assert_eq!(unsafe { N::from_abi(js) }.get(), Some(hash));
+    }
+}
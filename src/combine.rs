@@ -0,0 +1,107 @@
+use crate::murmur3::fmix64;
+use crate::FloatHashOf;
+use std::hash::BuildHasher;
+
+// An arbitrary odd constant, 2^64 / golden ratio, so that a * K + b is not symmetric in a and b
+const K: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Combines the raw bits of two hashes, before they are mapped onto a float
+pub(crate) fn combine_bits(a: u64, b: u64) -> u64 {
+    fmix64(a.wrapping_mul(K).wrapping_add(b))
+}
+
+/// Hashes made from other hashes. Each works on the bits of the floats, as read through a
+/// `BigUint64Array` over the same memory as a `Float64Array`, so it can be done in JavaScript:
+///
+/// ```js
+/// const M = (1n << 64n) - 1n;
+/// const fmix64 = (k) => {
+///   k ^= k >> 33n; k = (k * 0xff51afd7ed558ccdn) & M;
+///   k ^= k >> 33n; k = (k * 0xc4ceb9fe1a85ec53n) & M;
+///   return k ^ (k >> 33n);
+/// };
+/// // The mapping of hash_u64_to_f64: force the top two bits of the exponent to differ
+/// const toFloatBits = (h) => {
+///   const top = h & 0x6000000000000000n;
+///   if (top === 0n) return h | 0x4000000000000000n;
+///   if (top === 0x6000000000000000n) return h ^ 0x4000000000000000n;
+///   return h;
+/// };
+/// const combine = (a, b) => toFloatBits(fmix64((a * 0x9e3779b97f4a7c15n + b) & M));
+/// const combineUnordered = (hs) => toFloatBits(fmix64(hs.reduce((sum, h) => (sum + fmix64(h)) & M, 0n)));
+/// ```
+impl<T: ?Sized, S: BuildHasher> FloatHashOf<T, S> {
+    /// A hash of the pair `(a, b)`. Swapping `a` and `b` gives a different hash
+    pub fn combine<A: ?Sized, B: ?Sized>(a: FloatHashOf<A, S>, b: FloatHashOf<B, S>) -> Self {
+        Self::from_u64(combine_bits(a.to_bits(), b.to_bits()))
+    }
+
+    /// A hash of a collection which does not depend on the order of `hashes`, eg: for a set.
+    /// Repeated items are not cancelled out, so for a map combine each key with its value first.
+    pub fn combine_unordered<A, I>(hashes: I) -> Self
+    where
+        A: ?Sized,
+        I: IntoIterator<Item = FloatHashOf<A, S>>,
+    {
+        let sum = hashes
+            .into_iter()
+            .fold(0u64, |sum, hash| sum.wrapping_add(fmix64(hash.to_bits())));
+        Self::from_u64(fmix64(sum))
+    }
+
+    fn to_bits(self) -> u64 {
+        self.hash.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    type H = FloatHashOf<()>;
+
+    fn h(value: f64) -> H {
+        H::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn same_as_javascript() {
+        // From the JavaScript reference above
+        assert_eq!(
+            H::combine(h(1.5), h(-2.)).into_inner(),
+            -2.566782450580777e127
+        );
+        assert_eq!(
+            H::combine(h(-2.), h(1.5)).into_inner(),
+            -9.039356452250415e-41
+        );
+        assert_eq!(
+            H::combine_unordered(vec![h(1.5), h(-2.), h(3.)]).into_inner(),
+            -1.1380038803229722e-84
+        );
+        assert_eq!(H::combine_unordered(Vec::<H>::new()).into_inner(), 2.);
+    }
+
+    #[test]
+    fn order() {
+        let (a, b, c) = (h(1.5), h(-2.), h(3.));
+        assert_ne!(H::combine(a, b), H::combine(b, a));
+        assert_eq!(
+            H::combine_unordered(vec![a, b, c]),
+            H::combine_unordered(vec![c, a, b])
+        );
+        assert_ne!(
+            H::combine_unordered(vec![a, a]),
+            H::combine_unordered(vec![b, b])
+        );
+    }
+
+    #[test]
+    fn mixed_tags() {
+        let name = FloatHashOf::<str>::from("name");
+        let id = FloatHashOf::<u32>::from(&7);
+        let key = FloatHashOf::<(String, u32)>::combine(name, id);
+        assert!(FloatHashOf::<()>::try_from(key.into_inner()).is_ok());
+    }
+}
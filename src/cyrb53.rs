@@ -0,0 +1,121 @@
+use crate::tag_only_traits;
+use std::borrow::Borrow;
+use std::marker::PhantomData;
+
+/// cyrb53 over UTF-16 code units, exactly as the JavaScript:
+///
+/// ```js
+/// const cyrb53 = (str, seed = 0) => {
+///   let h1 = 0xdeadbeef ^ seed, h2 = 0x41c6ce57 ^ seed;
+///   for(let i = 0, ch; i < str.length; i++) {
+///     ch = str.charCodeAt(i);
+///     h1 = Math.imul(h1 ^ ch, 2654435761);
+///     h2 = Math.imul(h2 ^ ch, 1597334677);
+///   }
+///   h1  = Math.imul(h1 ^ (h1 >>> 16), 2246822507);
+///   h1 ^= Math.imul(h2 ^ (h2 >>> 13), 3266489909);
+///   h2  = Math.imul(h2 ^ (h2 >>> 16), 2246822507);
+///   h2 ^= Math.imul(h1 ^ (h1 >>> 13), 3266489909);
+///   return 4294967296 * (2097151 & h2) + (h1 >>> 0);
+/// };
+/// ```
+///
+/// The result is less than 2^53, so is exactly representable as a JavaScript number.
+pub fn cyrb53_utf16(units: &[u16], seed: u32) -> u64 {
+    cyrb53(units.iter().copied(), seed)
+}
+
+/// `cyrb53_utf16` of the UTF-16 encoding of `value`, which is what `charCodeAt` iterates in JavaScript.
+pub fn cyrb53_str(value: &str, seed: u32) -> u64 {
+    cyrb53(value.encode_utf16(), seed)
+}
+
+// Math.imul is a wrapping 32 bit multiply, and the bit pattern of the result is the same
+// whether the operands are treated as signed or unsigned, so everything here is u32.
+fn cyrb53(units: impl Iterator<Item = u16>, seed: u32) -> u64 {
+    let mut h1 = 0xdead_beef ^ seed;
+    let mut h2 = 0x41c6_ce57 ^ seed;
+    for unit in units {
+        let ch = u32::from(unit);
+        h1 = (h1 ^ ch).wrapping_mul(2_654_435_761);
+        h2 = (h2 ^ ch).wrapping_mul(1_597_334_677);
+    }
+    h1 = (h1 ^ (h1 >> 16)).wrapping_mul(2_246_822_507);
+    h1 ^= (h2 ^ (h2 >> 13)).wrapping_mul(3_266_489_909);
+    h2 = (h2 ^ (h2 >> 16)).wrapping_mul(2_246_822_507);
+    h2 ^= (h1 ^ (h1 >> 13)).wrapping_mul(3_266_489_909);
+
+    (u64::from(h2 & 0x1f_ffff) << 32) | u64::from(h1)
+}
+
+/// The 53 bit cyrb53 hash of a string, as the browser would compute it with `cyrb53(str, seed)`.
+/// Unlike `FloatHashOf` this can be computed in plain JavaScript, see `cyrb53_utf16`.
+pub struct Cyrb53HashOf<T: ?Sized> {
+    // cyrb53 can produce 0, so there is no niche to use here
+    hash: u64,
+    _marker: PhantomData<fn() -> *const T>,
+}
+
+tag_only_traits!(Cyrb53HashOf<T>);
+
+impl<T: ?Sized> Cyrb53HashOf<T> {
+    /// The number JavaScript's `cyrb53` returns. Always an integer, so this is exact
+    #[inline]
+    pub fn into_inner(self) -> f64 {
+        self.hash as f64
+    }
+
+    #[inline]
+    pub fn to_u64(self) -> u64 {
+        self.hash
+    }
+
+    pub fn with_seed<Q: AsRef<str> + ?Sized>(value: &Q, seed: u32) -> Self
+    where
+        T: Borrow<Q>,
+    {
+        Self {
+            hash: cyrb53_str(value.as_ref(), seed),
+            _marker: PhantomData,
+        }
+    }
+}
+
+// See the impl for FloatHashOf to explain the signature
+impl<T: AsRef<str> + ?Sized, Q: Borrow<T> + ?Sized> From<&T> for Cyrb53HashOf<Q> {
+    fn from(value: &T) -> Self {
+        Self::with_seed(value, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Produced by the JavaScript in the docs for cyrb53_utf16
+    #[test]
+    fn matches_javascript() {
+        assert_eq!(cyrb53_str("", 0), 3338908027751811);
+        assert_eq!(cyrb53_str("a", 0), 7929297801672961);
+        assert_eq!(cyrb53_str("b", 0), 8684336938537663);
+        assert_eq!(cyrb53_str("revenge", 0), 4051478007546757);
+        assert_eq!(cyrb53_str("revenue", 0), 8309097637345594);
+        assert_eq!(cyrb53_str("a", 1), 5368154436228575);
+        assert_eq!(cyrb53_str("hello", 0xdead_beef), 7983007116275835);
+        assert_eq!(cyrb53_str("héllo 😀", 0), 3084878568474421);
+        assert_eq!(cyrb53_utf16(&[0xd800], 0), 8925071026939333);
+        assert_eq!(cyrb53_str("\u{fffd}", 0), 7583962375297985);
+    }
+
+    #[test]
+    fn typed_wrapper() {
+        let x = Cyrb53HashOf::<String>::from("a");
+        let y = Cyrb53HashOf::<String>::from(&String::from("a"));
+        assert_eq!(x, y);
+        assert_eq!(x.into_inner(), 7929297801672961.);
+        assert_eq!(
+            Cyrb53HashOf::<String>::with_seed("a", 1).to_u64(),
+            5368154436228575
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use crate::combine::combine_bits;
+use crate::hasher::forward_writes;
+use crate::{hash_63_bits, DefaultBuildHasher, FloatHashOf, StableBuildHasher};
+use std::fmt;
+use std::hash::{BuildHasher, Hasher};
+use std::marker::PhantomData;
+
+/// Opts a tag type in to hashes which differ from those of every other tag, so that eg: a
+/// `DomainFloatHashOf<User>` cannot be mistaken for the `DomainFloatHashOf<Project>` of the same string.
+///
+/// The salted hashes are a different type, `DomainFloatHashOf<T>`, whose hasher mixes in the salt.
+/// So `From`, `from_equivalent`, the map and slice lookups and `FloatHasher` all salt for it,
+/// while `FloatHashOf<T>` stays unsalted for compatibility.
+///
+/// ```
+/// use float_hash_of::{DomainFloatHashOf, FloatHashOf, HashDomain};
+/// use std::borrow::Borrow;
+///
+/// #[derive(Hash)]
+/// struct UserName(String);
+///
+/// impl Borrow<str> for UserName {
+///     fn borrow(&self) -> &str {
+///         &self.0
+///     }
+/// }
+///
+/// impl HashDomain for UserName {
+///     const NAME: &'static str = "app.UserName";
+/// }
+///
+/// let user = DomainFloatHashOf::<UserName>::from("ada");
+/// assert_eq!(user, DomainFloatHashOf::from_unsalted(FloatHashOf::from("ada")));
+/// assert_ne!(user.into_inner(), FloatHashOf::<UserName>::from("ada").into_inner());
+/// ```
+pub trait HashDomain {
+    /// Names the domain. Hashes depend on it, so changing it changes every salted hash.
+    /// Pick something unique rather than relying on the Rust type name, which may be refactored.
+    const NAME: &'static str;
+
+    /// Mixed into every hash of the domain. By default the `StableHasher` hash of `NAME`,
+    /// which never changes, so JavaScript can hard-code it
+    fn salt() -> u64 {
+        StableBuildHasher::default().hash_one(Self::NAME)
+    }
+}
+
+/// Builds a `DomainHasher` around the hashers of `S`, salted for `T`
+pub struct DomainBuildHasher<T: ?Sized, S = DefaultBuildHasher> {
+    build_hasher: S,
+    // T::salt(), which hashes the name, so it is computed once here rather than per hash
+    salt: u64,
+    _marker: PhantomData<fn() -> *const T>,
+}
+
+impl<T: HashDomain + ?Sized, S> DomainBuildHasher<T, S> {
+    pub fn new(build_hasher: S) -> Self {
+        Self {
+            build_hasher,
+            salt: T::salt(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: HashDomain + ?Sized, S: Default> Default for DomainBuildHasher<T, S> {
+    fn default() -> Self {
+        Self::new(S::default())
+    }
+}
+
+impl<T: ?Sized, S: Clone> Clone for DomainBuildHasher<T, S> {
+    fn clone(&self) -> Self {
+        Self {
+            build_hasher: self.build_hasher.clone(),
+            salt: self.salt,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: ?Sized, S: fmt::Debug> fmt::Debug for DomainBuildHasher<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("DomainBuildHasher")
+            .field(&self.build_hasher)
+            .finish()
+    }
+}
+
+impl<T: HashDomain + ?Sized, S: BuildHasher> BuildHasher for DomainBuildHasher<T, S> {
+    type Hasher = DomainHasher<S::Hasher>;
+
+    #[inline]
+    fn build_hasher(&self) -> Self::Hasher {
+        DomainHasher {
+            hasher: self.build_hasher.build_hasher(),
+            salt: self.salt,
+        }
+    }
+}
+
+/// A hasher which finishes by combining a salt with the unsalted hash, as it would be mapped onto a float.
+/// So a salted hash is `combine` of the salt and the unsalted hash, see there for a JavaScript version.
+#[derive(Clone, Debug)]
+pub struct DomainHasher<H> {
+    hasher: H,
+    salt: u64,
+}
+
+impl<H: Hasher> Hasher for DomainHasher<H> {
+    #[inline]
+    fn finish(&self) -> u64 {
+        combine_bits(self.salt, hash_63_bits(self.hasher.finish()))
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.hasher.write(bytes)
+    }
+
+    forward_writes!();
+}
+
+/// A `FloatHashOf` salted for `T`. See `HashDomain`.
+pub type DomainFloatHashOf<T, S = DefaultBuildHasher> = FloatHashOf<T, DomainBuildHasher<T, S>>;
+
+impl<T: HashDomain + ?Sized, S: BuildHasher> FloatHashOf<T, DomainBuildHasher<T, S>> {
+    /// Salts a hash made without a domain, eg: one received from code which predates it
+    pub fn from_unsalted(hash: FloatHashOf<T, S>) -> Self {
+        Self::from_u64(combine_bits(T::salt(), hash.hash.get()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        FloatHashMap, FloatHashMapExt, FloatHashSliceExt, FloatHasher, HashEquivalent,
+        StableFloatHashOf,
+    };
+    use std::borrow::Borrow;
+
+    macro_rules! name {
+        ($name:ident) => {
+            #[derive(Hash)]
+            struct $name(String);
+
+            impl Borrow<str> for $name {
+                fn borrow(&self) -> &str {
+                    &self.0
+                }
+            }
+
+            impl HashDomain for $name {
+                const NAME: &'static str = stringify!($name);
+            }
+
+            impl HashEquivalent<$name> for str {}
+        };
+    }
+
+    name!(User);
+    name!(Project);
+
+    type StableDomainFloatHashOf<T> = DomainFloatHashOf<T, StableBuildHasher>;
+
+    #[test]
+    fn domains_differ() {
+        let user = DomainFloatHashOf::<User>::from("a");
+        let project = DomainFloatHashOf::<Project>::from("a");
+        assert_ne!(user.into_inner(), project.into_inner());
+        assert_ne!(
+            user.into_inner(),
+            FloatHashOf::<User>::from("a").into_inner()
+        );
+        assert_eq!(
+            user,
+            DomainFloatHashOf::from_unsalted(FloatHashOf::from("a"))
+        );
+    }
+
+    #[test]
+    fn salt_is_stable() {
+        assert_eq!(User::salt(), 0xc7e0_d6e3_57ad_4baa);
+        // From the JavaScript version of combine
+        let user = StableDomainFloatHashOf::<User>::from("a");
+        assert_eq!(user.into_inner(), 2.6804776854358768e-12);
+        assert_eq!(
+            StableDomainFloatHashOf::<User>::from_unsalted(StableFloatHashOf::from("a")),
+            user
+        );
+    }
+
+    #[test]
+    fn lookups_are_salted() {
+        let user = StableDomainFloatHashOf::<User>::from("a");
+        assert_eq!(StableDomainFloatHashOf::<User>::from_equivalent("a"), user);
+
+        let mut hasher = FloatHasher::<DomainBuildHasher<User, StableBuildHasher>>::new();
+        hasher.write_str("a");
+        assert_eq!(hasher.finish_float::<User>(), user);
+
+        let mut map =
+            FloatHashMap::<User, u32, DomainBuildHasher<User, StableBuildHasher>>::default();
+        map.insert(user, 1);
+        assert_eq!(map.get_by("a"), Some(&1));
+
+        let sorted = [user];
+        assert_eq!(sorted.binary_search_value("a"), Ok(0));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{FloatHashOf, PerItem};
+use std::hash::{BuildHasher, Hash};
+
+/// Implemented by types whose `Hash` writes exactly what `T`'s would for the matching value,
+/// eg: `(&str, u32)` for `(String, u32)`, or `[&str]` for `Vec<String>`.
+/// This lets a key be hashed from borrowed parts without building the owned `T`.
+/// See `FloatHashOf::from_equivalent`.
+///
+/// Slices of integers are only equivalent to slices of the same integers, eg: `[u32; 3]` for
+/// `Vec<u32>`, never `[&u32]`. See `HashesPerItem`. Any items may be borrowed inside `PerItem`,
+/// eg: `PerItem<&[&u32]>` for `PerItem<Vec<u32>>`.
+pub trait HashEquivalent<T: ?Sized>: Hash {}
+
+/// Types whose slices Rust hashes by hashing each item in turn, which is every type except the
+/// integers. A slice of integers is hashed as one write of its memory, while eg: `[&u32]` writes
+/// each integer separately, and hashers need not give the same result for both.
+/// Implement this for your own types to use slices of them with `HashEquivalent`, unless they
+/// override `Hash::hash_slice`, which `#[derive(Hash)]` does not.
+///
+/// ```compile_fail
+/// use float_hash_of::FloatHashOf;
+///
+/// FloatHashOf::<Vec<u32>>::from_equivalent(&vec![&1u32, &2, &3]);
+/// ```
+pub trait HashesPerItem {}
+
+macro_rules! reflexive {
+    ($($ty:ty),*) => {
+        $(impl HashEquivalent<$ty> for $ty {})*
+    };
+}
+
+reflexive! {
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, bool, char, ()
+}
+
+// String hashes as str
+impl HashEquivalent<str> for str {}
+impl HashEquivalent<String> for str {}
+impl HashEquivalent<String> for String {}
+impl HashEquivalent<str> for String {}
+
+// References and boxes hash as what they point to
+impl<A: HashEquivalent<T> + ?Sized, T: ?Sized> HashEquivalent<T> for &A {}
+impl<A: HashEquivalent<T> + ?Sized, T: ?Sized> HashEquivalent<T> for Box<A> {}
+
+impl<A: HashEquivalent<T>, T> HashEquivalent<Option<T>> for Option<A> {}
+
+impl HashesPerItem for bool {}
+impl HashesPerItem for char {}
+impl HashesPerItem for () {}
+impl HashesPerItem for String {}
+impl<A: ?Sized> HashesPerItem for &A {}
+impl<A: ?Sized> HashesPerItem for Box<A> {}
+impl<A> HashesPerItem for Option<A> {}
+impl<A> HashesPerItem for Vec<A> {}
+impl<A, const N: usize> HashesPerItem for [A; N] {}
+
+// Vec and arrays hash as slices, which write their length then each item. Only the items of T
+// are checked, since an A which is equivalent to a T that hashes per item does too.
+impl<A: HashEquivalent<T>, T: HashesPerItem> HashEquivalent<[T]> for [A] {}
+impl<A: HashEquivalent<T>, T: HashesPerItem> HashEquivalent<Vec<T>> for [A] {}
+impl<A: HashEquivalent<T>, T: HashesPerItem> HashEquivalent<[T]> for Vec<A> {}
+impl<A: HashEquivalent<T>, T: HashesPerItem> HashEquivalent<Vec<T>> for Vec<A> {}
+impl<A: HashEquivalent<T>, T: HashesPerItem, const N: usize> HashEquivalent<[T]> for [A; N] {}
+impl<A: HashEquivalent<T>, T: HashesPerItem, const N: usize> HashEquivalent<Vec<T>> for [A; N] {}
+impl<A: HashEquivalent<T>, T: HashesPerItem, const N: usize> HashEquivalent<[T; N]> for [A; N] {}
+
+// The same memory in each, so the same single write
+macro_rules! integer_slices {
+    ($($int:ty),*) => {
+        $(
+            impl HashEquivalent<[$int]> for [$int] {}
+            impl HashEquivalent<Vec<$int>> for [$int] {}
+            impl HashEquivalent<[$int]> for Vec<$int> {}
+            impl HashEquivalent<Vec<$int>> for Vec<$int> {}
+            impl<const N: usize> HashEquivalent<[$int]> for [$int; N] {}
+            impl<const N: usize> HashEquivalent<Vec<$int>> for [$int; N] {}
+            impl<const N: usize> HashEquivalent<[$int; N]> for [$int; N] {}
+        )*
+    };
+}
+
+integer_slices! {
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+}
+
+// Each writes its length then each item
+impl<A: HashEquivalent<T>, T> HashEquivalent<PerItem<Vec<T>>> for PerItem<&[A]> {}
+impl<A: HashEquivalent<T>, T> HashEquivalent<PerItem<Vec<T>>> for PerItem<Vec<A>> {}
+impl<A: HashEquivalent<T>, T, const N: usize> HashEquivalent<PerItem<Vec<T>>> for PerItem<[A; N]> {}
+impl<A: HashEquivalent<T>, T, const N: usize> HashEquivalent<PerItem<[T; N]>> for PerItem<&[A]> {}
+impl<A: HashEquivalent<T>, T, const N: usize> HashEquivalent<PerItem<[T; N]>> for PerItem<Vec<A>> {}
+impl<A: HashEquivalent<T>, T, const N: usize> HashEquivalent<PerItem<[T; N]>> for PerItem<[A; N]> {}
+impl<T> HashesPerItem for PerItem<T> {}
+
+// Tuples write each item in order, with nothing between
+macro_rules! tuples {
+    ($(($($a:ident $t:ident),+))*) => {
+        $(
+            impl<$($a: HashEquivalent<$t>, $t),+> HashEquivalent<($($t,)+)> for ($($a,)+) {}
+            impl<$($t),+> HashesPerItem for ($($t,)+) {}
+        )*
+    };
+}
+
+tuples! {
+    (A1 T1)
+    (A1 T1, A2 T2)
+    (A1 T1, A2 T2, A3 T3)
+    (A1 T1, A2 T2, A3 T3, A4 T4)
+    (A1 T1, A2 T2, A3 T3, A4 T4, A5 T5)
+    (A1 T1, A2 T2, A3 T3, A4 T4, A5 T5, A6 T6)
+    (A1 T1, A2 T2, A3 T3, A4 T4, A5 T5, A6 T6, A7 T7)
+    (A1 T1, A2 T2, A3 T3, A4 T4, A5 T5, A6 T6, A7 T7, A8 T8)
+}
+
+impl<T: ?Sized, S: BuildHasher + Default> FloatHashOf<T, S> {
+    /// The hash `T` would have, computed from a borrowed form of it. See `HashEquivalent`
+    pub fn from_equivalent<Q: HashEquivalent<T> + ?Sized>(value: &Q) -> Self {
+        Self::from_u64(S::default().hash_one(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PassThroughBuildHasher, StableFloatHashOf};
+
+    #[test]
+    fn tuple_of_borrowed_parts() {
+        let owned = (String::from("abc"), 7u32);
+        assert_eq!(
+            FloatHashOf::<(String, u32)>::from_equivalent(&("abc", 7u32)),
+            FloatHashOf::from(&owned)
+        );
+        assert_eq!(
+            StableFloatHashOf::<(String, u32)>::from_equivalent(&("abc", 7u32)),
+            StableFloatHashOf::from(&owned)
+        );
+    }
+
+    #[test]
+    fn sequences() {
+        let owned = vec![String::from("a"), String::from("bc")];
+        let expected = FloatHashOf::<Vec<String>>::from(&owned);
+        assert_eq!(FloatHashOf::from_equivalent(&["a", "bc"][..]), expected);
+        assert_eq!(FloatHashOf::from_equivalent(&["a", "bc"]), expected);
+        assert_eq!(FloatHashOf::from_equivalent(&vec!["a", "bc"]), expected);
+        assert_ne!(FloatHashOf::from_equivalent(&["abc"]), expected);
+
+        // Integers only as themselves, since Vec<u32> is one write of its memory
+        let numbers = vec![1u32, 2, 3];
+        assert_eq!(
+            FloatHashOf::<Vec<u32>, PassThroughBuildHasher>::from_equivalent(&[1u32, 2, 3]),
+            FloatHashOf::from(&numbers)
+        );
+
+        // Anything else hashes each item, so they can be borrowed
+        let options = vec![Some(1u32), None];
+        assert_eq!(
+            FloatHashOf::<Vec<Option<u32>>, PassThroughBuildHasher>::from_equivalent(&[
+                Some(&1u32),
+                None
+            ]),
+            FloatHashOf::from(&options)
+        );
+    }
+
+    #[test]
+    fn per_item() {
+        let owned = PerItem(vec![1u32, 2, 3]);
+        let expected = StableFloatHashOf::<PerItem<Vec<u32>>>::from(&owned);
+        assert_eq!(
+            StableFloatHashOf::from_equivalent(&PerItem(&[&1u32, &2, &3][..])),
+            expected
+        );
+        assert_eq!(
+            StableFloatHashOf::from_equivalent(&PerItem([1u32, 2, 3])),
+            expected
+        );
+    }
+
+    #[test]
+    fn nested() {
+        type Key = (Option<String>, Vec<(String, u8)>);
+        let owned: Key = (Some(String::from("x")), vec![(String::from("y"), 1)]);
+        assert_eq!(
+            FloatHashOf::<Key>::from_equivalent(&(Some("x"), [("y", 1u8)])),
+            FloatHashOf::from(&owned)
+        );
+    }
+}
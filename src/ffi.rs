@@ -0,0 +1,140 @@
+//! `extern "C"` functions for C and C++ code, which produce and check the same values as the Rust types.
+//! The matching header is `include/float_hash_of.h`, generated with `cbindgen` from `cbindgen.toml`.
+//!
+//! To link, build the crate as a static or dynamic library with the feature on, eg:
+//! `cargo rustc --release --features ffi --crate-type staticlib`
+
+use crate::{f64_to_hash_u64, hash_u64_to_f64, hash_u64_to_f64_lossless};
+use crate::{FloatHashOf, InvalidFloatHash, StableFloatHashOf};
+use std::{slice, str};
+
+/// The result of checking a double. Anything but `FLOAT_HASH_OF_STATUS_VALID` is the reason it is not a hash
+#[repr(C)]
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub enum FloatHashOfStatus {
+    Valid = 0,
+    Nan,
+    Zero,
+    Infinite,
+    Subnormal,
+    Exponent,
+}
+
+impl From<InvalidFloatHash> for FloatHashOfStatus {
+    fn from(err: InvalidFloatHash) -> Self {
+        match err {
+            InvalidFloatHash::NaN => FloatHashOfStatus::Nan,
+            InvalidFloatHash::Zero => FloatHashOfStatus::Zero,
+            InvalidFloatHash::Infinite => FloatHashOfStatus::Infinite,
+            InvalidFloatHash::Subnormal => FloatHashOfStatus::Subnormal,
+            InvalidFloatHash::Exponent => FloatHashOfStatus::Exponent,
+        }
+    }
+}
+
+// A null ptr is allowed for an empty string, since that is what C++ gives for an empty std::string_view
+unsafe fn utf8<'a>(ptr: *const u8, len: usize) -> Option<&'a str> {
+    if len == 0 {
+        return Some("");
+    }
+    str::from_utf8(slice::from_raw_parts(ptr, len)).ok()
+}
+
+/// `FloatHashOf::<String>::from(text)`, or 0 if the bytes are not UTF-8.
+/// Uses Rust's default hasher, so only matches Rust built with the same compiler release.
+///
+/// # Safety
+/// `ptr` must point to `len` readable bytes, or `len` must be 0
+#[no_mangle]
+pub unsafe extern "C" fn float_hash_of_hash_utf8(ptr: *const u8, len: usize) -> f64 {
+    utf8(ptr, len).map_or(0., |text| FloatHashOf::<str>::from(text).into_inner())
+}
+
+/// `StableFloatHashOf::<String>::from(text)`, or 0 if the bytes are not UTF-8
+///
+/// # Safety
+/// `ptr` must point to `len` readable bytes, or `len` must be 0
+#[no_mangle]
+pub unsafe extern "C" fn float_hash_of_hash_utf8_stable(ptr: *const u8, len: usize) -> f64 {
+    utf8(ptr, len).map_or(0., |text| StableFloatHashOf::<str>::from(text).into_inner())
+}
+
+/// Whether `value` could have come from any `FloatHashOf`, and if not, why
+#[no_mangle]
+pub extern "C" fn float_hash_of_validate(value: f64) -> FloatHashOfStatus {
+    match f64_to_hash_u64(value) {
+        Ok(_) => FloatHashOfStatus::Valid,
+        Err(err) => err.into(),
+    }
+}
+
+/// `hash_u64_to_f64`
+#[no_mangle]
+pub extern "C" fn float_hash_of_from_u64(hash: u64) -> f64 {
+    hash_u64_to_f64(hash)
+}
+
+/// `hash_u64_to_f64_lossless`
+#[no_mangle]
+pub extern "C" fn float_hash_of_from_u64_lossless(hash: u64) -> f64 {
+    hash_u64_to_f64_lossless(hash)
+}
+
+/// `f64_to_hash_u64`. Writes to `out` only when `value` is valid
+///
+/// # Safety
+/// `out` must be valid for a write
+#[no_mangle]
+pub unsafe extern "C" fn float_hash_of_to_u64(value: f64, out: *mut u64) -> FloatHashOfStatus {
+    match f64_to_hash_u64(value) {
+        Ok(hash) => {
+            out.write(hash);
+            FloatHashOfStatus::Valid
+        }
+        Err(err) => err.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_rust() {
+        let text = "hello";
+        unsafe {
+            assert_eq!(
+                float_hash_of_hash_utf8(text.as_ptr(), text.len()),
+                FloatHashOf::<String>::from(text).into_inner()
+            );
+            assert_eq!(
+                float_hash_of_hash_utf8_stable(std::ptr::null(), 0),
+                crate::STABLE_TEST_VECTORS[0].1
+            );
+            assert_eq!(float_hash_of_hash_utf8([0xffu8].as_ptr(), 1), 0.);
+        }
+    }
+
+    #[test]
+    fn validate_and_convert() {
+        let value = float_hash_of_from_u64_lossless(42);
+        let mut out = 0;
+        unsafe {
+            assert_eq!(
+                float_hash_of_to_u64(value, &mut out),
+                FloatHashOfStatus::Valid
+            );
+            assert_eq!(out, 42);
+            assert_eq!(
+                float_hash_of_to_u64(f64::NAN, &mut out),
+                FloatHashOfStatus::Nan
+            );
+        }
+        assert_eq!(float_hash_of_validate(0.), FloatHashOfStatus::Zero);
+        assert_eq!(float_hash_of_validate(1e300), FloatHashOfStatus::Exponent);
+        assert_eq!(
+            float_hash_of_validate(float_hash_of_from_u64(7)),
+            FloatHashOfStatus::Valid
+        );
+    }
+}
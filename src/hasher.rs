@@ -0,0 +1,138 @@
+use crate::{DefaultBuildHasher, FloatHashOf};
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::marker::PhantomData;
+
+/// A hasher from `S` which finishes with a `FloatHashOf`, for building a key from a few fields by hand.
+///
+/// ```
+/// use float_hash_of::{FloatHashOf, FloatHasher};
+/// use std::hash::{Hash, Hasher};
+///
+/// let mut hasher = FloatHasher::<float_hash_of::DefaultBuildHasher>::new();
+/// hasher.write_str("name");
+/// hasher.write_u32(7);
+/// let key: FloatHashOf<(String, u32)> = hasher.finish_float();
+///
+/// assert_eq!(key, FloatHashOf::from(&(String::from("name"), 7u32)));
+/// ```
+pub struct FloatHasher<S: BuildHasher = DefaultBuildHasher> {
+    hasher: S::Hasher,
+    _marker: PhantomData<fn() -> S>,
+}
+
+impl<S: BuildHasher> FloatHasher<S> {
+    pub fn new() -> Self
+    where
+        S: Default,
+    {
+        Self::with_hasher(&S::default())
+    }
+
+    /// For when `S` has state, eg: keys. See `FloatHashOf::with_hasher`
+    pub fn with_hasher(build_hasher: &S) -> Self {
+        Self {
+            hasher: build_hasher.build_hasher(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The same as `text.hash(self)`, which is what `Hasher::write_str` will do once it is stable
+    pub fn write_str(&mut self, text: &str) {
+        text.hash(self)
+    }
+
+    /// The hash of everything written so far. `T` is whatever the writes stand for
+    pub fn finish_float<T: ?Sized>(&self) -> FloatHashOf<T, S> {
+        FloatHashOf::from_u64(self.hasher.finish())
+    }
+}
+
+impl<S: BuildHasher + Default> Default for FloatHasher<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: BuildHasher> Clone for FloatHasher<S>
+where
+    S::Hasher: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            hasher: self.hasher.clone(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+// Every method is forwarded to `self.hasher`, since it may treat integers specially, eg: PortableHasher
+macro_rules! forward_writes {
+    () => {
+        $crate::hasher::forward_writes! {
+            write_u8: u8,
+            write_u16: u16,
+            write_u32: u32,
+            write_u64: u64,
+            write_u128: u128,
+            write_usize: usize,
+            write_i8: i8,
+            write_i16: i16,
+            write_i32: i32,
+            write_i64: i64,
+            write_i128: i128,
+            write_isize: isize,
+        }
+    };
+    ($($method:ident: $int:ty,)*) => {
+        $(
+            #[inline]
+            fn $method(&mut self, i: $int) {
+                self.hasher.$method(i)
+            }
+        )*
+    };
+}
+pub(crate) use forward_writes;
+
+impl<S: BuildHasher> Hasher for FloatHasher<S> {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hasher.finish()
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.hasher.write(bytes)
+    }
+
+    forward_writes!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PortableBuildHasher, StableBuildHasher, StableFloatHashOf};
+
+    #[test]
+    fn same_as_hashing_the_value() {
+        let mut hasher = FloatHasher::<StableBuildHasher>::new();
+        hasher.write_str("a");
+        assert_eq!(
+            hasher.finish_float::<str>(),
+            StableFloatHashOf::<str>::from("a")
+        );
+        assert_eq!(
+            hasher.finish_float::<str>().into_inner(),
+            crate::STABLE_TEST_VECTORS[1].1
+        );
+    }
+
+    #[test]
+    fn integers_reach_the_inner_hasher() {
+        type P = PortableBuildHasher<DefaultBuildHasher>;
+        let mut hasher = FloatHasher::<P>::new();
+        hasher.write_usize(3);
+        let expected: FloatHashOf<u64, P> = FloatHashOf::from(&3u64);
+        assert_eq!(hasher.finish_float(), expected);
+    }
+}
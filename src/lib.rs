@@ -1,48 +1,246 @@
 use hash_of::*;
 use std::borrow::Borrow;
-use std::hash::Hash;
+use std::collections::hash_map::DefaultHasher;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
 use std::marker::PhantomData;
 use std::num::NonZeroU64;
 
+#[cfg(feature = "wasm-bindgen")]
+mod bindgen;
+mod combine;
+mod cyrb53;
+mod domain;
+mod equivalent;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod hasher;
+mod map;
+mod murmur3;
+mod nullable;
+mod ord;
+mod portable;
+mod safe_int;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod slice;
+mod smi;
+mod stable;
+mod text;
+mod utf16;
+
+pub use cyrb53::{cyrb53_str, cyrb53_utf16, Cyrb53HashOf};
+pub use domain::{DomainBuildHasher, DomainFloatHashOf, DomainHasher, HashDomain};
+pub use equivalent::{HashEquivalent, HashesPerItem};
+pub use hasher::FloatHasher;
+pub use map::{
+    FloatHashMap, FloatHashMapExt, FloatHashSet, FloatHashSetExt, PassThroughBuildHasher,
+    PassThroughHasher,
+};
+pub use murmur3::{
+    murmur3_x64_128, murmur3_x64_128_str, murmur3_x86_32, murmur3_x86_32_str, Murmur3X64_128,
+    Murmur3X86_32,
+};
+pub use nullable::{NullableFloatHash, OptionFloatHashExt};
+pub use ord::FloatHashSliceExt;
+pub use portable::{PerItem, PortableBuildHasher, PortableFloatHashOf, PortableHasher};
+pub use safe_int::{SafeIntHashOf, MAX_SAFE_INTEGER};
+pub use slice::InvalidFloatHashAt;
+pub use smi::SmiHashOf;
+pub use stable::{
+    verify_stable_hashing, StableBuildHasher, StableFloatHashOf, StableHashMismatch, StableHasher,
+    StableValueTestVector, STABLE_TEST_VECTORS, STABLE_VALUE_TEST_VECTORS,
+};
+pub use text::{Formatted, ParseFloatHashError, TextFormat};
+
+/// The hasher used by `HashOf`, and so by `FloatHashOf` unless another is chosen.
+/// Note that Rust does not promise this algorithm will stay the same between releases.
+pub type DefaultBuildHasher = BuildHasherDefault<DefaultHasher>;
+
 /// Takes a 64 bit hash, and makes a primitive 63 bit hash using a double.
 /// This allows for easy comparison without keeping making heap allocations in JavaScript (eg: string) or requiring low entropy (int)
-#[derive(Eq, PartialEq, Debug, Hash)]
-pub struct FloatHashOf<T> {
+///
+/// `S` picks the hash algorithm. Hashes made with different algorithms are different types.
+///
+/// `T` is only a tag, so it may be unsized (eg: `FloatHashOf<str>`) and need not implement anything.
+/// The hash is `Send + Sync` and covariant in `T` regardless of what `T` is.
+///
+/// The layout is guaranteed to be that of an `f64`, see `as_f64_slice`.
+#[repr(transparent)]
+pub struct FloatHashOf<T: ?Sized, S: BuildHasher = DefaultBuildHasher> {
     // There's no such thing as a NonZeroF64, so store as NonZeroU64 and transmute when necessary.
     // This let's us store it in Option without increasing the size.
     hash: NonZeroU64,
-    _marker: PhantomData<*const T>, // Indicate we do not own T
+    // Indicate we do not own T or S. The fn keeps us Send + Sync even when *const T is not
+    _marker: PhantomData<fn() -> (*const T, S)>,
 }
 
-// Manually implementing Copy/Clone because they are not automatically derived
-// if T does not equal Copy/Clone
-impl<T> Clone for FloatHashOf<T> {
-    fn clone(&self) -> Self {
-        *self
-    }
+// Implements the traits every hash type has by hand, because derive would only implement them
+// when T (and S) do, eg: the default BuildHasher doesn't implement Hash.
+// None of them depend on T or S, since only the `hash` field is compared.
+macro_rules! tag_only_traits {
+    ($name:ident<T $(, $s:ident: $bound:ident)?>) => {
+        $crate::tag_only_traits!($name<T $(, $s: $bound)?> without Debug);
+
+        impl<T: ?Sized $(, $s: $bound)?> std::fmt::Debug for $name<T $(, $s)?> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("hash", &self.hash)
+                    .finish()
+            }
+        }
+    };
+    ($name:ident<T $(, $s:ident: $bound:ident)?> without Debug) => {
+        impl<T: ?Sized $(, $s: $bound)?> Clone for $name<T $(, $s)?> {
+            fn clone(&self) -> Self {
+                *self
+            }
+        }
+
+        impl<T: ?Sized $(, $s: $bound)?> Copy for $name<T $(, $s)?> {}
+
+        impl<T: ?Sized $(, $s: $bound)?> PartialEq for $name<T $(, $s)?> {
+            #[inline]
+            fn eq(&self, other: &Self) -> bool {
+                self.hash == other.hash
+            }
+        }
+
+        impl<T: ?Sized $(, $s: $bound)?> Eq for $name<T $(, $s)?> {}
+
+        impl<T: ?Sized $(, $s: $bound)?> std::hash::Hash for $name<T $(, $s)?> {
+            #[inline]
+            fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                std::hash::Hash::hash(&self.hash, state)
+            }
+        }
+    };
 }
+pub(crate) use tag_only_traits;
 
-impl<T> Copy for FloatHashOf<T> {}
+tag_only_traits!(FloatHashOf<T, S: BuildHasher>);
 
-impl<T> FloatHashOf<T> {
+impl<T: ?Sized, S: BuildHasher> FloatHashOf<T, S> {
     #[inline]
     pub fn into_inner(self) -> f64 {
         f64::from_bits(self.hash.get())
     }
+
+    /// Retags the hash as a hash of `U`, eg: to go between `FloatHashOf<String>` and `FloatHashOf<str>`.
+    /// The value is unchanged, so this is only meaningful when `U` hashes the same as `T`.
+    #[inline]
+    pub fn cast<U: ?Sized>(self) -> FloatHashOf<U, S> {
+        FloatHashOf {
+            hash: self.hash,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Recovers the 63 bit integer that `hash_u64_to_f64_lossless` would map to this value.
+    /// Every `FloatHashOf` has one, regardless of the `Encoding` that produced it.
+    /// For an `Encoding::Folded` hash it is not the hash that was folded, and `hash_u64_to_f64`
+    /// does not map it back to this value. Only `hash_u64_to_f64_lossless` does.
+    #[inline]
+    pub fn to_hash_u64(self) -> u64 {
+        unhash_63_bits_lossless(self.hash.get())
+    }
+
+    /// The bytes of the float in little-endian order, as seen through a `Float64Array` on every JavaScript engine in use
+    #[inline]
+    pub fn to_le_bytes(self) -> [u8; 8] {
+        self.hash.get().to_le_bytes()
+    }
+
+    /// The bytes of the float in big-endian order, as eg: `DataView.setFloat64` writes by default
+    #[inline]
+    pub fn to_be_bytes(self) -> [u8; 8] {
+        self.hash.get().to_be_bytes()
+    }
+
+    pub fn try_from_le_bytes(bytes: [u8; 8]) -> Result<Self, InvalidFloatHash> {
+        Self::try_from(u64::from_le_bytes(bytes))
+    }
+
+    pub fn try_from_be_bytes(bytes: [u8; 8]) -> Result<Self, InvalidFloatHash> {
+        Self::try_from(u64::from_be_bytes(bytes))
+    }
+
+    /// The approximate probability that at least two of `keys` distinct values share a hash
+    pub fn collision_probability(keys: u64) -> f64 {
+        // 2^63 outputs. See hash_63_bits_lossless
+        birthday_probability((1u64 << 63) as f64, keys)
+    }
+
+    /// Hashes `value` with a hasher from `build_hasher`.
+    /// For when `S` has state, eg: keys, and so cannot be used through `From`
+    pub fn with_hasher<Q: Hash + ?Sized>(value: &Q, build_hasher: &S) -> Self
+    where
+        T: Borrow<Q>,
+    {
+        Self::from_u64(build_hasher.hash_one(value))
+    }
+
+    /// As `with_hasher`, but mapped onto a float with `encoding`.
+    /// With `Encoding::Lossless`, `to_hash_u64` recovers the hash less its top bit
+    pub fn with_hasher_encoded<Q: Hash + ?Sized>(
+        value: &Q,
+        build_hasher: &S,
+        encoding: Encoding,
+    ) -> Self
+    where
+        T: Borrow<Q>,
+    {
+        Self::from_encoded_u64(build_hasher.hash_one(value), encoding)
+    }
+
+    fn from_u64(hash: u64) -> Self {
+        Self::from_encoded_u64(hash, Encoding::Folded)
+    }
+
+    fn from_encoded_u64(hash: u64, encoding: Encoding) -> Self {
+        let hash = encoding.encode_bits(hash);
+
+        Self {
+            hash: unsafe { NonZeroU64::new_unchecked(hash) },
+            _marker: PhantomData,
+        }
+    }
 }
 
+impl<T> FloatHashOf<T> {
+    /// Maps a hash from `HashOf` onto a float with `encoding`.
+    /// `Encoding::Folded` gives the same value as `From<HashOf<T>>`
+    pub fn from_hash_with(hash: HashOf<T>, encoding: Encoding) -> Self {
+        Self::from_encoded_u64(hash.to_inner(), encoding)
+    }
+}
+
+// The usual approximation for the birthday problem, 1 - e^(-k(k-1)/2n).
+// expm1 keeps precision when the probability is tiny, which is the interesting case for wide hashes.
+pub(crate) fn birthday_probability(outputs: f64, keys: u64) -> f64 {
+    let keys = keys as f64;
+    let pairs = keys * (keys - 1.) / 2.;
+    -(-pairs / outputs).exp_m1()
+}
+
+// Grouping matches 64bit IEEE 754 float
+#[allow(clippy::inconsistent_digit_grouping, clippy::unusual_byte_groupings)]
+const EXP_2: u64 = 0b0_11000000000_0000000000000000000000000000000000000000000000000000;
+#[allow(clippy::inconsistent_digit_grouping, clippy::unusual_byte_groupings)]
+const EXP_1: u64 = 0b0_10000000000_0000000000000000000000000000000000000000000000000000;
+#[allow(clippy::inconsistent_digit_grouping, clippy::unusual_byte_groupings)]
+const EXP_0: u64 = 0b0_00000000000_0000000000000000000000000000000000000000000000000000;
+
 // We want to avoid NaN (unexpected equality rules), subnormals (they may be slow?),
-// and +-0 (inconsistent equality rules, useful as a null hash)
+// and +-0 (inconsistent equality rules, useful as a null hash, see NullableFloatHash)
 // so ensure there is at least one each of 0 and 1 in the exponent to make those cases impossible.
 // This also rules out +-INF
+// The masks apply to the integer value of the bits, which f64::from_bits interprets the same way
+// on every target, so there is nothing to do for big-endian here. Byte order only matters once
+// the value is turned into bytes, see FloatHashOf::to_le_bytes
 fn hash_63_bits(hash: u64) -> u64 {
-    #![allow(clippy::inconsistent_digit_grouping)] // Grouping matches 64bit IEEE 754 float
-
-    // TODO: This assumes little-endian, but we could cgf the big-endian format in
-    const EXP_2: u64 = 0b0_11000000000_0000000000000000000000000000000000000000000000000000;
-    const EXP_1: u64 = 0b0_10000000000_0000000000000000000000000000000000000000000000000000;
-    const EXP_0: u64 = 0b0_00000000000_0000000000000000000000000000000000000000000000000000;
-
     match hash & EXP_2 {
         EXP_0 => hash | EXP_1,
         EXP_2 => hash ^ EXP_1,
@@ -54,32 +252,187 @@ pub fn hash_u64_to_f64(hash: u64) -> f64 {
     f64::from_bits(hash_63_bits(hash))
 }
 
-impl<T> From<HashOf<T>> for FloatHashOf<T> {
-    fn from(hash: HashOf<T>) -> Self {
-        let mut hash = hash.to_inner();
-        hash = hash_63_bits(hash);
+// A bijection between 0..2^63 and the outputs of hash_63_bits.
+// Those outputs have a free sign bit, 1 bit of choice in the top two bits of the exponent (01 or 10),
+// and 61 free bits below that. So:
+//   bit 62 of the input is the sign bit
+//   bit 61 of the input is the top bit of the exponent, and the next bit is its complement
+//   bits 0..=60 of the input are unchanged
+// The top bit of the input is ignored.
+fn hash_63_bits_lossless(hash: u64) -> u64 {
+    const SIGN: u64 = 1 << 63;
+    const LOW_61: u64 = (1 << 61) - 1;
 
-        Self {
-            hash: unsafe { NonZeroU64::new_unchecked(hash) },
-            _marker: PhantomData,
+    let sign = (hash << 1) & SIGN;
+    let exp = if hash & (1 << 61) == 0 {
+        EXP_1 >> 1
+    } else {
+        EXP_1
+    };
+    sign | exp | (hash & LOW_61)
+}
+
+// Inverse of hash_63_bits_lossless, assuming the input has already been validated
+fn unhash_63_bits_lossless(bits: u64) -> u64 {
+    const SIGN: u64 = 1 << 63;
+    const LOW_61: u64 = (1 << 61) - 1;
+
+    ((bits & SIGN) >> 1) | ((bits & EXP_1) >> 1) | (bits & LOW_61)
+}
+
+/// Like `hash_u64_to_f64`, but only the low 63 bits of `hash` are used and each of those maps to
+/// a distinct float, so the result can be turned back into `hash & (u64::MAX >> 1)` with `f64_to_hash_u64`
+pub fn hash_u64_to_f64_lossless(hash: u64) -> f64 {
+    f64::from_bits(hash_63_bits_lossless(hash))
+}
+
+/// The inverse of `hash_u64_to_f64_lossless`. Fails if `value` could not have been produced by this crate.
+pub fn f64_to_hash_u64(value: f64) -> Result<u64, InvalidFloatHash> {
+    let bits = validate_63_bits(value.to_bits())?;
+    Ok(unhash_63_bits_lossless(bits.get()))
+}
+
+/// Selects how a 64 bit hash is mapped onto a float
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub enum Encoding {
+    /// The mapping of `hash_u64_to_f64`, used by `From`.
+    /// Every input bit affects the output, but pairs of inputs share an output so it cannot be inverted.
+    #[default]
+    Folded,
+    /// The mapping of `hash_u64_to_f64_lossless`.
+    /// The top input bit is ignored, and the remaining 63 bits can be recovered with `f64_to_hash_u64`.
+    Lossless,
+}
+
+impl Encoding {
+    fn encode_bits(self, hash: u64) -> u64 {
+        match self {
+            Encoding::Folded => hash_63_bits(hash),
+            Encoding::Lossless => hash_63_bits_lossless(hash),
         }
     }
+
+    /// Maps `hash` onto a float, as `hash_u64_to_f64` or `hash_u64_to_f64_lossless` would
+    pub fn encode(self, hash: u64) -> f64 {
+        f64::from_bits(self.encode_bits(hash))
+    }
+}
+
+/// The rule that a value failed when it was checked against the outputs of `hash_63_bits`
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum InvalidFloatHash {
+    NaN,
+    Zero,
+    Infinite,
+    Subnormal,
+    /// The top two bits of the exponent were both 0 or both 1
+    Exponent,
+}
+
+impl fmt::Display for InvalidFloatHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            InvalidFloatHash::NaN => "NaN is never a float hash",
+            InvalidFloatHash::Zero => "+-0 is never a float hash",
+            InvalidFloatHash::Infinite => "+-INF is never a float hash",
+            InvalidFloatHash::Subnormal => "subnormals are never a float hash",
+            InvalidFloatHash::Exponent => {
+                "the top two bits of the exponent must differ in a float hash"
+            }
+        };
+        f.write_str(reason)
+    }
+}
+
+impl Error for InvalidFloatHash {}
+
+// Checks the rules in the order a reader would expect to see them reported,
+// so that eg: NaN is reported as NaN rather than as a bad exponent.
+fn validate_63_bits(bits: u64) -> Result<NonZeroU64, InvalidFloatHash> {
+    let value = f64::from_bits(bits);
+    if value.is_nan() {
+        return Err(InvalidFloatHash::NaN);
+    }
+    if value == 0. {
+        return Err(InvalidFloatHash::Zero);
+    }
+    if value.is_infinite() {
+        return Err(InvalidFloatHash::Infinite);
+    }
+    if value.is_subnormal() {
+        return Err(InvalidFloatHash::Subnormal);
+    }
+    match bits & EXP_2 {
+        EXP_0 | EXP_2 => Err(InvalidFloatHash::Exponent),
+        // Safety: At least one bit of the exponent is set
+        _ => Ok(unsafe { NonZeroU64::new_unchecked(bits) }),
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> TryFrom<u64> for FloatHashOf<T, S> {
+    type Error = InvalidFloatHash;
+    /// Accepts the raw bits of a float previously produced by this crate
+    fn try_from(bits: u64) -> Result<Self, Self::Error> {
+        Ok(Self {
+            hash: validate_63_bits(bits)?,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> TryFrom<f64> for FloatHashOf<T, S> {
+    type Error = InvalidFloatHash;
+    /// Accepts a float previously produced by this crate, eg: one sent back from JavaScript
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Self::try_from(value.to_bits())
+    }
+}
+
+impl<T> From<HashOf<T>> for FloatHashOf<T> {
+    fn from(hash: HashOf<T>) -> Self {
+        Self::from_u64(hash.to_inner())
+    }
 }
 
 // Example types to explain the confusing signature...
 // T: str
 // Q: String
-impl<T: Hash + ?Sized, Q: Borrow<T>> From<&T> for FloatHashOf<Q> {
+// For the default S this is the same hash as HashOf<Q>
+impl<T: Hash + ?Sized, Q: Borrow<T> + ?Sized, S: BuildHasher + Default> From<&T>
+    for FloatHashOf<Q, S>
+{
     fn from(value: &T) -> Self {
-        let hash = HashOf::<Q>::from(value);
-        hash.into()
+        Self::with_hasher(value, &S::default())
     }
 }
 
+// The same From impls for the hash types which have no S, eg: SafeIntHashOf. Each needs a
+// `from_u64` which reduces a hash. hash_one gives the same hash as HashOf<Q> without Q: Sized
+macro_rules! default_hasher_from {
+    ($name:ident) => {
+        impl<T> From<hash_of::HashOf<T>> for $name<T> {
+            fn from(hash: hash_of::HashOf<T>) -> Self {
+                Self::from_u64(hash.to_inner())
+            }
+        }
+
+        impl<T: std::hash::Hash + ?Sized, Q: std::borrow::Borrow<T> + ?Sized> From<&T>
+            for $name<Q>
+        {
+            fn from(value: &T) -> Self {
+                let build_hasher = $crate::DefaultBuildHasher::default();
+                Self::from_u64(std::hash::BuildHasher::hash_one(&build_hasher, value))
+            }
+        }
+    };
+}
+pub(crate) use default_hasher_from;
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::collections::HashSet;
+    use std::hash::Hasher;
 
     fn test_cases() -> HashSet<u64> {
         let mut cases = HashSet::new();
@@ -102,4 +455,178 @@ mod tests {
             assert!(!result.is_nan());
         }
     }
+
+    // These expect fixed values so that they also check big-endian targets,
+    // eg: `cross test --target s390x-unknown-linux-gnu`
+    #[test]
+    fn same_value_on_every_target() {
+        assert_eq!(hash_u64_to_f64(0), 2.);
+        assert_eq!(hash_u64_to_f64(u64::MAX), -1.9999999999999998);
+        assert_eq!(hash_u64_to_f64(0x3ff8_0000_0000_0000), 1.5);
+        assert_eq!(hash_u64_to_f64(0x7ff8_0000_0000_0000), 1.5);
+        assert_eq!(hash_u64_to_f64_lossless(0x1ff8_0000_0000_0000), 1.5);
+    }
+
+    #[test]
+    fn byte_order() {
+        let hash = FloatHashOf::<()>::try_from(1.5).unwrap();
+        let le = [0, 0, 0, 0, 0, 0, 0xf8, 0x3f];
+        let be = [0x3f, 0xf8, 0, 0, 0, 0, 0, 0];
+        assert_eq!(hash.to_le_bytes(), le);
+        assert_eq!(hash.to_be_bytes(), be);
+        assert_eq!(FloatHashOf::try_from_le_bytes(le), Ok(hash));
+        assert_eq!(FloatHashOf::try_from_be_bytes(be), Ok(hash));
+        assert_eq!(
+            FloatHashOf::<()>::try_from_le_bytes(be),
+            Err(InvalidFloatHash::Subnormal)
+        );
+    }
+
+    #[test]
+    fn round_trips_through_try_from() {
+        for &case in test_cases().iter() {
+            let result = hash_u64_to_f64(case);
+            let hash = FloatHashOf::<()>::try_from(result).unwrap();
+            assert_eq!(hash.into_inner().to_bits(), result.to_bits());
+        }
+    }
+
+    #[test]
+    fn lossless_round_trips() {
+        for &case in test_cases().iter() {
+            let result = hash_u64_to_f64_lossless(case);
+            assert!(FloatHashOf::<()>::try_from(result).is_ok());
+            assert_eq!(f64_to_hash_u64(result), Ok(case & (u64::MAX >> 1)));
+        }
+    }
+
+    #[test]
+    fn from_hash_with_encoding() {
+        let hash = HashOf::<String>::from("test");
+        let lossless = FloatHashOf::from_hash_with(hash, Encoding::Lossless);
+        assert_eq!(lossless.to_hash_u64(), hash.to_inner() & (u64::MAX >> 1));
+        let folded = FloatHashOf::from_hash_with(hash, Encoding::default());
+        assert_eq!(folded, FloatHashOf::from(hash));
+    }
+
+    #[test]
+    fn with_hasher_encoded() {
+        let build_hasher = StableBuildHasher::default();
+        let hash = build_hasher.hash_one("test");
+        let lossless = StableFloatHashOf::<str>::with_hasher_encoded(
+            "test",
+            &build_hasher,
+            Encoding::Lossless,
+        );
+        assert_eq!(lossless.to_hash_u64(), hash & (u64::MAX >> 1));
+        let folded =
+            StableFloatHashOf::<str>::with_hasher_encoded("test", &build_hasher, Encoding::Folded);
+        assert_eq!(folded, StableFloatHashOf::from("test"));
+    }
+
+    #[derive(Default)]
+    struct SumHasher(u64);
+
+    impl Hasher for SumHasher {
+        fn write(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.0 += u64::from(byte);
+            }
+        }
+        fn finish(&self) -> u64 {
+            self.0
+        }
+    }
+
+    fn assert_auto_traits<T: Send + Sync + Unpin + 'static>() {}
+
+    // Would not compile if FloatHashOf were invariant in T
+    fn covariant<'a>(hash: FloatHashOf<&'static str>) -> FloatHashOf<&'a str> {
+        hash
+    }
+
+    struct NotEq(#[allow(dead_code)] std::rc::Rc<f32>);
+
+    #[test]
+    fn tags_need_nothing() {
+        assert_auto_traits::<FloatHashOf<NotEq>>();
+        assert_auto_traits::<FloatHashOf<str>>();
+        assert_auto_traits::<FloatHashOf<[u8]>>();
+
+        let x = FloatHashOf::<NotEq>::try_from(1.).unwrap();
+        assert_eq!(x, x);
+        assert_eq!(
+            format!("{:?}", x),
+            "FloatHashOf { hash: 4607182418800017408 }"
+        );
+
+        let s = String::from("test");
+        let y = covariant(FloatHashOf::from(&"test"));
+        assert_eq!(y, FloatHashOf::from(&s.as_str()));
+    }
+
+    #[test]
+    fn unsized_tags() {
+        let owned = FloatHashOf::<String>::from("test");
+        let unsized_ = FloatHashOf::<str>::from("test");
+        assert_eq!(owned.cast::<str>(), unsized_);
+        assert_eq!(unsized_.cast::<String>(), owned);
+
+        let bytes = FloatHashOf::<[u8]>::from(&[1u8, 2][..]);
+        assert_eq!(bytes, FloatHashOf::<Vec<u8>>::from(&vec![1, 2]).cast());
+    }
+
+    #[test]
+    fn default_hasher_matches_hash_of() {
+        let x = FloatHashOf::<String>::from("test");
+        let y = FloatHashOf::from(HashOf::<String>::from("test"));
+        assert_eq!(x, y);
+    }
+
+    #[test]
+    fn pluggable_hasher() {
+        type Sum = BuildHasherDefault<SumHasher>;
+        let x = FloatHashOf::<u32, Sum>::from(&0x01_02_03);
+        assert_eq!(x.into_inner().to_bits(), hash_63_bits(6));
+
+        let state = std::collections::hash_map::RandomState::new();
+        let y = FloatHashOf::<String, _>::with_hasher("test", &state);
+        let z = FloatHashOf::<String, _>::with_hasher("test", &state);
+        assert_eq!(y, z);
+    }
+
+    #[test]
+    fn lossless_is_onto() {
+        // Every valid float has a preimage, so decoding then encoding is the identity
+        for &case in test_cases().iter() {
+            let hash = FloatHashOf::<u64>::from(&case);
+            let bits = hash.to_hash_u64();
+            assert!(bits < 1 << 63);
+            assert_eq!(
+                hash_u64_to_f64_lossless(bits).to_bits(),
+                hash.into_inner().to_bits()
+            );
+        }
+    }
+
+    #[test]
+    fn try_from_reports_rule() {
+        type H = FloatHashOf<()>;
+        assert_eq!(H::try_from(f64::NAN), Err(InvalidFloatHash::NaN));
+        assert_eq!(H::try_from(0.), Err(InvalidFloatHash::Zero));
+        assert_eq!(H::try_from(-0.), Err(InvalidFloatHash::Zero));
+        assert_eq!(H::try_from(f64::INFINITY), Err(InvalidFloatHash::Infinite));
+        assert_eq!(
+            H::try_from(f64::NEG_INFINITY),
+            Err(InvalidFloatHash::Infinite)
+        );
+        assert_eq!(H::try_from(1u64), Err(InvalidFloatHash::Subnormal));
+        assert_eq!(H::try_from(f64::MAX), Err(InvalidFloatHash::Exponent));
+        assert_eq!(
+            H::try_from(f64::MIN_POSITIVE),
+            Err(InvalidFloatHash::Exponent)
+        );
+        assert!(H::try_from(1.).is_ok());
+        assert!(H::try_from(-2.).is_ok());
+    }
 }
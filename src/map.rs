@@ -0,0 +1,175 @@
+use crate::{DefaultBuildHasher, FloatHashOf};
+use std::borrow::Borrow;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, BuildHasherDefault, Hash, Hasher};
+
+/// A `HashMap` keyed by hashes, which does not hash them again. `S` is the hasher of the keys
+pub type FloatHashMap<T, V, S = DefaultBuildHasher> =
+    HashMap<FloatHashOf<T, S>, V, PassThroughBuildHasher>;
+
+/// A `HashSet` of hashes, which does not hash them again. `S` is the hasher of the values
+pub type FloatHashSet<T, S = DefaultBuildHasher> =
+    HashSet<FloatHashOf<T, S>, PassThroughBuildHasher>;
+
+pub type PassThroughBuildHasher = BuildHasherDefault<PassThroughHasher>;
+
+/// A `Hasher` for keys which are already a hash, eg: `FloatHashOf`.
+/// Other keys still work, but with a much weaker hash than usual.
+#[derive(Copy, Clone, Default, Debug)]
+pub struct PassThroughHasher(u64);
+
+impl Hasher for PassThroughHasher {
+    // The bits of a FloatHashOf are mostly random, but the top of the exponent is always 01 or 10.
+    // std's map takes a tag from the top 7 bits and the bucket from the bottom bits, so multiply
+    // to fold every bit into the top. The bottom bits are random mantissa either way.
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.0 = self.0.rotate_left(5) ^ i;
+    }
+
+    // Only reached for keys other than hashes, so this need not be fast
+    fn write(&mut self, bytes: &[u8]) {
+        for chunk in bytes.chunks(8) {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.write_u64(u64::from_le_bytes(word));
+        }
+    }
+}
+
+/// Lookups by the value which was hashed, so callers need not make the `FloatHashOf` themselves
+pub trait FloatHashMapExt<T: ?Sized, V, S: BuildHasher> {
+    fn get_by<Q: Hash + ?Sized>(&self, value: &Q) -> Option<&V>
+    where
+        T: Borrow<Q>,
+        S: Default;
+
+    fn get_mut_by<Q: Hash + ?Sized>(&mut self, value: &Q) -> Option<&mut V>
+    where
+        T: Borrow<Q>,
+        S: Default;
+
+    fn contains_key_by<Q: Hash + ?Sized>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        S: Default;
+
+    fn remove_by<Q: Hash + ?Sized>(&mut self, value: &Q) -> Option<V>
+    where
+        T: Borrow<Q>,
+        S: Default;
+}
+
+impl<T: ?Sized, V, S: BuildHasher, B: BuildHasher> FloatHashMapExt<T, V, S>
+    for HashMap<FloatHashOf<T, S>, V, B>
+{
+    fn get_by<Q: Hash + ?Sized>(&self, value: &Q) -> Option<&V>
+    where
+        T: Borrow<Q>,
+        S: Default,
+    {
+        self.get(&FloatHashOf::from(value))
+    }
+
+    fn get_mut_by<Q: Hash + ?Sized>(&mut self, value: &Q) -> Option<&mut V>
+    where
+        T: Borrow<Q>,
+        S: Default,
+    {
+        self.get_mut(&FloatHashOf::from(value))
+    }
+
+    fn contains_key_by<Q: Hash + ?Sized>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        S: Default,
+    {
+        self.contains_key(&FloatHashOf::from(value))
+    }
+
+    fn remove_by<Q: Hash + ?Sized>(&mut self, value: &Q) -> Option<V>
+    where
+        T: Borrow<Q>,
+        S: Default,
+    {
+        self.remove(&FloatHashOf::from(value))
+    }
+}
+
+/// Lookups by the value which was hashed, as `FloatHashMapExt`
+pub trait FloatHashSetExt<T: ?Sized, S: BuildHasher> {
+    fn contains_by<Q: Hash + ?Sized>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        S: Default;
+
+    fn remove_by<Q: Hash + ?Sized>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        S: Default;
+}
+
+impl<T: ?Sized, S: BuildHasher, B: BuildHasher> FloatHashSetExt<T, S>
+    for HashSet<FloatHashOf<T, S>, B>
+{
+    fn contains_by<Q: Hash + ?Sized>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        S: Default,
+    {
+        self.contains(&FloatHashOf::from(value))
+    }
+
+    fn remove_by<Q: Hash + ?Sized>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        S: Default,
+    {
+        self.remove(&FloatHashOf::from(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_by_value() {
+        let mut map = FloatHashMap::<String, u32>::default();
+        map.insert(FloatHashOf::from("one"), 1);
+        map.insert(FloatHashOf::from("two"), 2);
+
+        assert_eq!(map.get_by("one"), Some(&1));
+        assert_eq!(map.get_by(&String::from("two")), Some(&2));
+        assert!(!map.contains_key_by("three"));
+
+        *map.get_mut_by("one").unwrap() += 10;
+        assert_eq!(map.remove_by("one"), Some(11));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn set_by_value() {
+        let mut set: FloatHashSet<str> = ["a", "b"].iter().map(|&s| FloatHashOf::from(s)).collect();
+        assert!(set.contains_by("a"));
+        assert!(set.remove_by("b"));
+        assert!(!set.contains_by("b"));
+    }
+
+    #[test]
+    fn top_bits_vary() {
+        // Without the mix the 2nd and 3rd bits of every hash would differ, leaving 64 tags
+        let tops: HashSet<u64> = (0..64u32)
+            .map(|i| {
+                let key = FloatHashOf::<u32>::from(&i);
+                PassThroughBuildHasher::default().hash_one(key) >> 57
+            })
+            .collect();
+        assert!(tops.len() > 32);
+    }
+}
@@ -0,0 +1,218 @@
+use crate::{hash_63_bits, FloatHashOf};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+use std::num::NonZeroU64;
+
+/// MurmurHash3_x86_32 of `bytes`
+pub fn murmur3_x86_32(bytes: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+
+    let mut h1 = seed;
+    let mut blocks = bytes.chunks_exact(4);
+    for block in &mut blocks {
+        let mut k1 = u32::from_le_bytes([block[0], block[1], block[2], block[3]]);
+        k1 = k1.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        h1 ^= k1;
+        h1 = h1.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+
+    let tail = blocks.remainder();
+    if !tail.is_empty() {
+        let mut k1 = 0;
+        for (i, &byte) in tail.iter().enumerate() {
+            k1 |= u32::from(byte) << (8 * i);
+        }
+        h1 ^= k1.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    }
+
+    h1 ^= bytes.len() as u32;
+    fmix32(h1)
+}
+
+/// MurmurHash3_x64_128 of `bytes`. The first 64 bit half is the high bits of the result,
+/// so formatting it with `{:032x}` gives the usual hex string.
+pub fn murmur3_x64_128(bytes: &[u8], seed: u32) -> u128 {
+    const C1: u64 = 0x87c3_7b91_1142_53d5;
+    const C2: u64 = 0x4cf5_ad43_2745_937f;
+
+    let mut h1 = u64::from(seed);
+    let mut h2 = u64::from(seed);
+    let mut blocks = bytes.chunks_exact(16);
+    for block in &mut blocks {
+        let (k1, k2) = (read_u64_le(&block[..8]), read_u64_le(&block[8..]));
+
+        h1 ^= k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2);
+        h1 = h1.rotate_left(27).wrapping_add(h2);
+        h1 = h1.wrapping_mul(5).wrapping_add(0x52dc_e729);
+
+        h2 ^= k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1);
+        h2 = h2.rotate_left(31).wrapping_add(h1);
+        h2 = h2.wrapping_mul(5).wrapping_add(0x3849_5ab5);
+    }
+
+    let tail = blocks.remainder();
+    if tail.len() > 8 {
+        let k2 = read_u64_le(&tail[8..]);
+        h2 ^= k2.wrapping_mul(C2).rotate_left(33).wrapping_mul(C1);
+    }
+    if !tail.is_empty() {
+        let k1 = read_u64_le(&tail[..tail.len().min(8)]);
+        h1 ^= k1.wrapping_mul(C1).rotate_left(31).wrapping_mul(C2);
+    }
+
+    h1 ^= bytes.len() as u64;
+    h2 ^= bytes.len() as u64;
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+    h1 = fmix64(h1);
+    h2 = fmix64(h2);
+    h1 = h1.wrapping_add(h2);
+    h2 = h2.wrapping_add(h1);
+
+    (u128::from(h1) << 64) | u128::from(h2)
+}
+
+// Up to 8 bytes, little-endian, missing bytes are 0
+fn read_u64_le(bytes: &[u8]) -> u64 {
+    let mut word = [0; 8];
+    word[..bytes.len()].copy_from_slice(bytes);
+    u64::from_le_bytes(word)
+}
+
+fn fmix32(mut h: u32) -> u32 {
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85eb_ca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2_ae35);
+    h ^= h >> 16;
+    h
+}
+
+pub(crate) fn fmix64(mut k: u64) -> u64 {
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    k ^= k >> 33;
+    k = k.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    k ^= k >> 33;
+    k
+}
+
+// The JavaScript libraries read strings with `charCodeAt(i) & 0xff`,
+// which is exact for strings with no code unit above U+00FF
+fn js_bytes(value: &str) -> Vec<u8> {
+    value.encode_utf16().map(|unit| unit as u8).collect()
+}
+
+/// MurmurHash3_x86_32 of a string as murmurhash-js's `murmurhash3_32_gc(value, seed)` reads it,
+/// taking the low byte of each UTF-16 code unit.
+/// imurmurhash agrees for strings where every code unit is at most U+00FF.
+pub fn murmur3_x86_32_str(value: &str, seed: u32) -> u32 {
+    murmur3_x86_32(&js_bytes(value), seed)
+}
+
+/// MurmurHash3_x64_128 of a string read the same way as `murmur3_x86_32_str`, as murmurhash3js's `x64.hash128` does
+pub fn murmur3_x64_128_str(value: &str, seed: u32) -> u128 {
+    murmur3_x64_128(&js_bytes(value), seed)
+}
+
+// The S of hashes made by from_murmur3_x86_32 and from_murmur3_x64_128, so that their type records
+// the algorithm. Neither can be built, so nothing which would hash a value with S compiles for them,
+// eg: From, get_by or binary_search_value, which would otherwise silently compute a different hash.
+macro_rules! murmur3_marker {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug)]
+        pub enum $name {}
+
+        impl BuildHasher for $name {
+            // Never built
+            type Hasher = DefaultHasher;
+
+            fn build_hasher(&self) -> Self::Hasher {
+                match *self {}
+            }
+        }
+    };
+}
+
+murmur3_marker! {
+    /// The `S` of `FloatHashOf::from_murmur3_x86_32`, whose values are integers in `1..2^32`
+    ///
+    /// ```compile_fail
+    /// use float_hash_of::{FloatHashOf, Murmur3X86_32};
+    ///
+    /// FloatHashOf::<str, Murmur3X86_32>::from("hello");
+    /// ```
+    Murmur3X86_32
+}
+
+murmur3_marker! {
+    /// The `S` of `FloatHashOf::from_murmur3_x64_128`
+    Murmur3X64_128
+}
+
+/// Keys which JavaScript can compute with existing MurmurHash3 libraries rather than through this crate
+impl<T: ?Sized> FloatHashOf<T, Murmur3X86_32> {
+    /// The number returned by murmurhash-js for the same string and seed.
+    /// `None` if that number is 0 (1 in 2^32 strings), which is never a valid `FloatHashOf`.
+    pub fn from_murmur3_x86_32(value: &str, seed: u32) -> Option<Self> {
+        let hash = f64::from(murmur3_x86_32_str(value, seed)).to_bits();
+        NonZeroU64::new(hash).map(|hash| Self {
+            hash,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<T: ?Sized> FloatHashOf<T, Murmur3X64_128> {
+    /// The first 16 hex digits of murmurhash3js's `x64.hash128`, mapped as `hash_u64_to_f64` does
+    pub fn from_murmur3_x64_128(value: &str, seed: u32) -> Self {
+        let hash = (murmur3_x64_128_str(value, seed) >> 64) as u64;
+        Self {
+            hash: unsafe { NonZeroU64::new_unchecked(hash_63_bits(hash)) },
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn x86_32_reference() {
+        assert_eq!(murmur3_x86_32(b"", 0), 0);
+        assert_eq!(murmur3_x86_32(b"", 1), 0x514e_28b7);
+        assert_eq!(murmur3_x86_32(b"", 0xffff_ffff), 0x81f1_6f39);
+        assert_eq!(murmur3_x86_32(b"hello", 0), 0x248b_fa47);
+        assert_eq!(
+            murmur3_x86_32(b"The quick brown fox jumps over the lazy dog", 0),
+            0x2e4f_f723
+        );
+    }
+
+    #[test]
+    fn x64_128_reference() {
+        assert_eq!(murmur3_x64_128(b"", 0), 0);
+        assert_eq!(
+            murmur3_x64_128(b"hello", 0),
+            0xcbd8_a7b3_41bd_9b02_5b1e_906a_48ae_1d19
+        );
+        assert_eq!(
+            murmur3_x64_128(b"The quick brown fox jumps over the lazy dog", 0),
+            0xe34b_bc7b_bc07_1b6c_7a43_3ca9_c49a_9347
+        );
+    }
+
+    #[test]
+    fn float_hash() {
+        type H = FloatHashOf<String, Murmur3X86_32>;
+        let hash = H::from_murmur3_x86_32("hello", 0).unwrap();
+        assert_eq!(hash.into_inner(), 613153351.);
+        assert_eq!(H::try_from(613153351.), Ok(hash));
+        assert_eq!(H::from_murmur3_x86_32("", 0), None);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{
+    tag_only_traits, validate_63_bits, DefaultBuildHasher, FloatHashOf, InvalidFloatHash,
+    InvalidFloatHashAt,
+};
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::num::NonZeroU64;
+use std::slice;
+
+/// Conversions between an optional hash and a number which is 0 when there is no hash.
+/// 0 is never a `FloatHashOf`, so this cannot be confused with any hash.
+pub trait OptionFloatHashExt: Sized {
+    /// `None` becomes +0
+    fn into_nullable_f64(self) -> f64;
+
+    /// Both +0 and -0 become `None`, as JavaScript does not tell them apart with `===`
+    fn try_from_nullable_f64(value: f64) -> Result<Self, InvalidFloatHash>;
+}
+
+impl<T: ?Sized, S: BuildHasher> OptionFloatHashExt for Option<FloatHashOf<T, S>> {
+    #[inline]
+    fn into_nullable_f64(self) -> f64 {
+        self.map_or(0., FloatHashOf::into_inner)
+    }
+
+    fn try_from_nullable_f64(value: f64) -> Result<Self, InvalidFloatHash> {
+        if value == 0. {
+            Ok(None)
+        } else {
+            FloatHashOf::try_from(value).map(Some)
+        }
+    }
+}
+
+/// An `Option<FloatHashOf>` which is seen by JavaScript and serde as a number, with 0 for `None`.
+/// For sparse arrays where 0 is "no value", eg: a `Float64Array`.
+///
+/// The layout is guaranteed to be that of an `f64`, with `None` as +0.
+#[repr(transparent)]
+pub struct NullableFloatHash<T: ?Sized, S: BuildHasher = DefaultBuildHasher> {
+    // Option<NonZeroU64> and transparent wrappers of it are guaranteed to use 0 for None
+    hash: Option<FloatHashOf<T, S>>,
+}
+
+tag_only_traits!(NullableFloatHash<T, S: BuildHasher> without Debug);
+
+impl<T: ?Sized, S: BuildHasher> fmt::Debug for NullableFloatHash<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NullableFloatHash")
+            .field(&self.hash)
+            .finish()
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> Default for NullableFloatHash<T, S> {
+    fn default() -> Self {
+        Self::NULL
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> NullableFloatHash<T, S> {
+    /// No hash, which is +0 in JavaScript
+    pub const NULL: Self = Self { hash: None };
+
+    pub fn new(hash: Option<FloatHashOf<T, S>>) -> Self {
+        Self { hash }
+    }
+
+    pub fn get(self) -> Option<FloatHashOf<T, S>> {
+        self.hash
+    }
+
+    pub fn is_null(self) -> bool {
+        self.hash.is_none()
+    }
+
+    /// The number JavaScript sees, which is +0 for no hash
+    pub fn into_inner(self) -> f64 {
+        self.hash.into_nullable_f64()
+    }
+
+    /// Views values as the numbers JavaScript sees
+    pub fn as_f64_slice(values: &[Self]) -> &[f64] {
+        // Safety: Transparent over Option<FloatHashOf>, which is the same size as the NonZeroU64
+        // in it, with +0 as None. Every such bit pattern is a valid f64
+        unsafe { slice::from_raw_parts(values.as_ptr() as *const f64, values.len()) }
+    }
+
+    /// Views numbers as nullable hashes without copying, after checking each is +0 or a float hash.
+    /// -0 is rejected, since a view cannot change it to +0. See `try_from_f64_slice_mut`
+    pub fn try_from_f64_slice(values: &[f64]) -> Result<&[Self], InvalidFloatHashAt> {
+        for (index, value) in values.iter().enumerate() {
+            if let Err(reason) = validate_nullable_bits(value.to_bits()) {
+                return Err(InvalidFloatHashAt { index, reason });
+            }
+        }
+        // Safety: See as_f64_slice. Every value was just validated
+        Ok(unsafe { slice::from_raw_parts(values.as_ptr() as *const Self, values.len()) })
+    }
+
+    /// As `try_from_f64_slice`, but accepts -0 by changing it to +0 in place.
+    /// On error values before the index may have been changed.
+    pub fn try_from_f64_slice_mut(values: &mut [f64]) -> Result<&mut [Self], InvalidFloatHashAt> {
+        for (index, value) in values.iter_mut().enumerate() {
+            if *value == 0. {
+                *value = 0.;
+            }
+            if let Err(reason) = validate_nullable_bits(value.to_bits()) {
+                return Err(InvalidFloatHashAt { index, reason });
+            }
+        }
+        // Safety: See try_from_f64_slice. Any Self written through the result is also a valid f64
+        Ok(unsafe { slice::from_raw_parts_mut(values.as_mut_ptr() as *mut Self, values.len()) })
+    }
+}
+
+// Only +0 is accepted for None, since only it has the bits of None
+fn validate_nullable_bits(bits: u64) -> Result<Option<NonZeroU64>, InvalidFloatHash> {
+    if bits == 0 {
+        Ok(None)
+    } else {
+        validate_63_bits(bits).map(Some)
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> From<Option<FloatHashOf<T, S>>> for NullableFloatHash<T, S> {
+    fn from(hash: Option<FloatHashOf<T, S>>) -> Self {
+        Self::new(hash)
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> From<FloatHashOf<T, S>> for NullableFloatHash<T, S> {
+    fn from(hash: FloatHashOf<T, S>) -> Self {
+        Self::new(Some(hash))
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> From<NullableFloatHash<T, S>> for Option<FloatHashOf<T, S>> {
+    fn from(nullable: NullableFloatHash<T, S>) -> Self {
+        nullable.hash
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> TryFrom<f64> for NullableFloatHash<T, S> {
+    type Error = InvalidFloatHash;
+    /// Accepts +-0 as no hash
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Option::try_from_nullable_f64(value).map(Self::new)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+mod bytemuck_impls {
+    use super::*;
+    use bytemuck::{CheckedBitPattern, NoUninit};
+
+    // Safety: A single Option<NonZeroU64> with no padding
+    unsafe impl<T, S> NoUninit for NullableFloatHash<T, S>
+    where
+        T: ?Sized + 'static,
+        S: BuildHasher + 'static,
+    {
+    }
+
+    // Safety: The bits are checked by the same rules as try_from_f64_slice
+    unsafe impl<T, S> CheckedBitPattern for NullableFloatHash<T, S>
+    where
+        T: ?Sized + 'static,
+        S: BuildHasher + 'static,
+    {
+        type Bits = u64;
+
+        fn is_valid_bit_pattern(bits: &u64) -> bool {
+            validate_nullable_bits(*bits).is_ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type H = FloatHashOf<str>;
+    type N = NullableFloatHash<str>;
+
+    #[test]
+    fn zero_is_none() {
+        let hash = H::from("a");
+        assert_eq!(Some(hash).into_nullable_f64(), hash.into_inner());
+        assert_eq!(None::<H>.into_nullable_f64().to_bits(), 0);
+
+        assert_eq!(Option::<H>::try_from_nullable_f64(0.), Ok(None));
+        assert_eq!(Option::<H>::try_from_nullable_f64(-0.), Ok(None));
+        assert_eq!(
+            Option::<H>::try_from_nullable_f64(hash.into_inner()),
+            Ok(Some(hash))
+        );
+        assert_eq!(
+            Option::<H>::try_from_nullable_f64(f64::NAN),
+            Err(InvalidFloatHash::NaN)
+        );
+    }
+
+    #[test]
+    fn same_layout_as_option() {
+        assert_eq!(std::mem::size_of::<N>(), 8);
+        assert_eq!(N::NULL.into_inner().to_bits(), 0);
+        assert_eq!(N::try_from(-0.).unwrap(), N::NULL);
+        assert_eq!(N::default(), N::NULL);
+    }
+
+    #[test]
+    fn slices() {
+        let hash = H::from("a");
+        let values = [N::from(hash), N::NULL, N::from(H::from("b"))];
+        let numbers = N::as_f64_slice(&values);
+        assert_eq!(numbers[..2], [hash.into_inner(), 0.]);
+        assert_eq!(N::try_from_f64_slice(numbers).unwrap(), &values);
+
+        let mut sparse = [hash.into_inner(), -0., 0.];
+        let err = N::try_from_f64_slice(&sparse).unwrap_err();
+        assert_eq!(err.index, 1);
+
+        let view = N::try_from_f64_slice_mut(&mut sparse).unwrap();
+        assert_eq!(view, &[N::from(hash), N::NULL, N::NULL]);
+        view[2] = N::from(hash);
+        assert_eq!(sparse[1].to_bits(), 0);
+        assert_eq!(sparse[2], hash.into_inner());
+
+        let mut bad = [1e300];
+        assert_eq!(
+            N::try_from_f64_slice_mut(&mut bad).unwrap_err().reason,
+            InvalidFloatHash::Exponent
+        );
+    }
+}
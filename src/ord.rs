@@ -0,0 +1,90 @@
+use crate::FloatHashOf;
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::hash::{BuildHasher, Hash};
+
+// Orders by numeric value, which is what JavaScript's `array.sort((a, b) => a - b)` does.
+// total_cmp only disagrees with numeric order for NaN and for -0 against +0, none of which
+// are ever a FloatHashOf, so this is consistent with Eq on the bits.
+impl<T: ?Sized, S: BuildHasher> Ord for FloatHashOf<T, S> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.into_inner().total_cmp(&other.into_inner())
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> PartialOrd for FloatHashOf<T, S> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Lookups on slices of hashes sorted by their `Ord`, eg: with `sort_unstable`
+pub trait FloatHashSliceExt<T: ?Sized, S: BuildHasher> {
+    /// Finds a number received from JavaScript without needing to validate it first.
+    /// Returns `Err` with the insertion point, as `binary_search` does.
+    fn binary_search_f64(&self, value: f64) -> Result<usize, usize>;
+
+    /// Finds the hash of `value`
+    fn binary_search_value<Q: Hash + ?Sized>(&self, value: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        S: Default;
+}
+
+impl<T: ?Sized, S: BuildHasher> FloatHashSliceExt<T, S> for [FloatHashOf<T, S>] {
+    fn binary_search_f64(&self, value: f64) -> Result<usize, usize> {
+        self.binary_search_by(|probe| probe.into_inner().total_cmp(&value))
+    }
+
+    fn binary_search_value<Q: Hash + ?Sized>(&self, value: &Q) -> Result<usize, usize>
+    where
+        T: Borrow<Q>,
+        S: Default,
+    {
+        self.binary_search(&FloatHashOf::from(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn sorts_like_javascript() {
+        let values = [3.5, -1e150, 1e-150, -2., 1., 7e20, -1e-150];
+        let mut hashes: Vec<FloatHashOf<()>> = values
+            .iter()
+            .map(|&value| FloatHashOf::try_from(value).unwrap())
+            .collect();
+        hashes.sort();
+
+        // What (a, b) => a - b does
+        let mut expected = values.to_vec();
+        expected.sort_by(|a, b| (a - b).partial_cmp(&0.).unwrap());
+
+        let sorted: Vec<f64> = hashes.iter().map(|hash| hash.into_inner()).collect();
+        assert_eq!(sorted, expected);
+    }
+
+    #[test]
+    fn binary_search() {
+        let mut hashes: Vec<FloatHashOf<String>> = ["a", "b", "c", "d"]
+            .iter()
+            .map(|&value| FloatHashOf::from(value))
+            .collect();
+        hashes.sort_unstable();
+
+        let found = hashes.binary_search_value("c").unwrap();
+        assert_eq!(hashes[found], FloatHashOf::from("c"));
+        assert_eq!(
+            hashes.binary_search_f64(hashes[found].into_inner()),
+            Ok(found)
+        );
+        assert!(hashes.binary_search_value("e").is_err());
+        assert_eq!(hashes.binary_search_f64(f64::NAN), Err(hashes.len()));
+        assert_eq!(hashes.binary_search_f64(f64::NEG_INFINITY), Err(0));
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{DefaultBuildHasher, FloatHashOf};
+use std::hash::{BuildHasher, Hash, Hasher};
+
+// Implements the integer methods of Hasher by writing little-endian bytes,
+// widening usize and isize to 64 bits. Every byte reaches the hasher through `self.write`
+macro_rules! portable_integer_writes {
+    () => {
+        #[inline]
+        fn write_u16(&mut self, i: u16) {
+            self.write(&i.to_le_bytes())
+        }
+
+        #[inline]
+        fn write_u32(&mut self, i: u32) {
+            self.write(&i.to_le_bytes())
+        }
+
+        #[inline]
+        fn write_u64(&mut self, i: u64) {
+            self.write(&i.to_le_bytes())
+        }
+
+        #[inline]
+        fn write_u128(&mut self, i: u128) {
+            self.write(&i.to_le_bytes())
+        }
+
+        #[inline]
+        fn write_usize(&mut self, i: usize) {
+            self.write_u64(i as u64)
+        }
+
+        #[inline]
+        fn write_i16(&mut self, i: i16) {
+            self.write_u16(i as u16)
+        }
+
+        #[inline]
+        fn write_i32(&mut self, i: i32) {
+            self.write_u32(i as u32)
+        }
+
+        #[inline]
+        fn write_i64(&mut self, i: i64) {
+            self.write_u64(i as u64)
+        }
+
+        #[inline]
+        fn write_i128(&mut self, i: i128) {
+            self.write_u128(i as u128)
+        }
+
+        #[inline]
+        fn write_isize(&mut self, i: isize) {
+            self.write_i64(i as i64)
+        }
+    };
+}
+pub(crate) use portable_integer_writes;
+
+/// Wraps a hasher so that it sees the same bytes on every target.
+///
+/// Integers are written as little-endian bytes, and `usize`/`isize` (including the lengths of slices and strings)
+/// are widened to 64 bits, so eg: a `Vec<String>` hashes the same on wasm32 as on x86_64.
+///
+/// The exception is slices of integers wider than a byte, eg: `Vec<u32>` or `[usize]`,
+/// which Rust hashes as one write of their memory and so cannot be normalised here.
+/// Wrap them in `PerItem` in keys which must match across targets.
+#[derive(Clone, Debug, Default)]
+pub struct PortableHasher<H>(H);
+
+impl<H: Hasher> PortableHasher<H> {
+    pub fn new(hasher: H) -> Self {
+        PortableHasher(hasher)
+    }
+}
+
+impl<H: Hasher> Hasher for PortableHasher<H> {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes)
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+
+    portable_integer_writes!();
+}
+
+/// Builds a `PortableHasher` around the hashers of `S`
+#[derive(Clone, Debug, Default)]
+pub struct PortableBuildHasher<S>(S);
+
+impl<S: BuildHasher> PortableBuildHasher<S> {
+    pub fn new(build_hasher: S) -> Self {
+        PortableBuildHasher(build_hasher)
+    }
+}
+
+impl<S: BuildHasher> BuildHasher for PortableBuildHasher<S> {
+    type Hasher = PortableHasher<S::Hasher>;
+
+    #[inline]
+    fn build_hasher(&self) -> Self::Hasher {
+        PortableHasher(self.0.build_hasher())
+    }
+}
+
+/// A `FloatHashOf` which is the same on every target for a given build. See `PortableHasher`.
+/// Note that the default `S` may still change between Rust releases, see `StableFloatHashOf` for that.
+///
+/// Sequences of integers wider than a byte, eg: `Vec<u32>`, `Vec<u64>` or `[usize]`, still hash
+/// differently on big-endian targets, and for `usize`/`isize` on 32 bit targets. Use `PerItem` for them:
+///
+/// ```
+/// use float_hash_of::{PerItem, PortableFloatHashOf};
+///
+/// let ids = PortableFloatHashOf::<PerItem<Vec<u32>>>::from(&PerItem(vec![1, 2, 3]));
+/// let borrowed = PortableFloatHashOf::<PerItem<Vec<u32>>>::from_equivalent(&PerItem(&[1u32, 2, 3][..]));
+/// assert_eq!(ids, borrowed);
+/// ```
+pub type PortableFloatHashOf<T, S = DefaultBuildHasher> = FloatHashOf<T, PortableBuildHasher<S>>;
+
+/// A sequence which is hashed as its length then each item in turn.
+///
+/// Rust already hashes sequences of most types this way, but hashes sequences of integers as one write of
+/// their memory, which depends on the byte order and the width of `usize` of the target. With `PerItem`
+/// each integer goes through the hasher on its own, so `PortableHasher` and `StableHasher` can normalise it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PerItem<T>(pub T);
+
+fn hash_per_item<A: Hash, H: Hasher>(items: &[A], state: &mut H) {
+    state.write_usize(items.len());
+    for item in items {
+        item.hash(state);
+    }
+}
+
+impl<A: Hash> Hash for PerItem<Vec<A>> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_per_item(&self.0, state)
+    }
+}
+
+impl<A: Hash, const N: usize> Hash for PerItem<[A; N]> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_per_item(&self.0, state)
+    }
+}
+
+impl<A: Hash> Hash for PerItem<&[A]> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        hash_per_item(self.0, state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, Hash};
+
+    // Only records what reaches `write`
+    #[derive(Default)]
+    struct Recorder(Vec<u8>);
+
+    impl Hasher for Recorder {
+        fn write(&mut self, bytes: &[u8]) {
+            self.0.extend_from_slice(bytes)
+        }
+        fn finish(&self) -> u64 {
+            0
+        }
+    }
+
+    fn record<T: Hash + ?Sized>(value: &T) -> Vec<u8> {
+        let mut hasher = PortableHasher::new(Recorder::default());
+        value.hash(&mut hasher);
+        hasher.0 .0
+    }
+
+    #[test]
+    fn lengths_are_64_bit() {
+        let value = vec![String::from("a"), String::from("b")];
+        let expected = [2, 0, 0, 0, 0, 0, 0, 0, b'a', 0xff, b'b', 0xff];
+        assert_eq!(record(&value), expected);
+    }
+
+    #[test]
+    fn integers_are_little_endian() {
+        let value = (0x0102u16, -2isize, 'a');
+        let expected = [2, 1, 254, 255, 255, 255, 255, 255, 255, 255, b'a', 0, 0, 0];
+        assert_eq!(record(&value), expected);
+    }
+
+    #[test]
+    fn per_item_integers() {
+        let expected = [2, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0];
+        assert_eq!(record(&PerItem(vec![1u32, 2])), expected);
+        assert_eq!(record(&PerItem([1u32, 2])), expected);
+        assert_eq!(record(&PerItem(&[1u32, 2][..])), expected);
+
+        let wide = record(&PerItem(vec![1usize, 2]));
+        assert_eq!(wide[8..], [1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn float_hash() {
+        type Portable =
+            PortableBuildHasher<BuildHasherDefault<std::collections::hash_map::DefaultHasher>>;
+        let x = PortableFloatHashOf::<Vec<String>>::from(&vec![String::from("a")]);
+        let y = FloatHashOf::<Vec<String>, Portable>::from(&vec![String::from("a")]);
+        assert_eq!(x, y);
+    }
+}
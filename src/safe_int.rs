@@ -0,0 +1,103 @@
+use crate::{birthday_probability, default_hasher_from, tag_only_traits};
+use std::marker::PhantomData;
+use std::num::NonZeroU64;
+
+/// JavaScript's `Number.MAX_SAFE_INTEGER`, 2^53 - 1
+pub const MAX_SAFE_INTEGER: u64 = (1 << 53) - 1;
+
+/// Takes a 64 bit hash, and reduces it to an integer in `1..=Number.MAX_SAFE_INTEGER`.
+/// For when JavaScript needs the hash to pass `Number.isSafeInteger`, eg: IndexedDB keys or array indices
+pub struct SafeIntHashOf<T: ?Sized> {
+    // Never 0, so that Option<SafeIntHashOf> is no larger
+    hash: NonZeroU64,
+    _marker: PhantomData<fn() -> *const T>,
+}
+
+tag_only_traits!(SafeIntHashOf<T>);
+
+impl<T: ?Sized> SafeIntHashOf<T> {
+    #[inline]
+    pub fn to_u64(self) -> u64 {
+        self.hash.get()
+    }
+
+    /// Always an integer, so this is exact
+    #[inline]
+    pub fn to_f64(self) -> f64 {
+        self.hash.get() as f64
+    }
+
+    /// As `FloatHashOf::collision_probability`, but with only `MAX_SAFE_INTEGER` possible hashes
+    pub fn collision_probability(keys: u64) -> f64 {
+        birthday_probability(MAX_SAFE_INTEGER as f64, keys)
+    }
+}
+
+// There are MAX_SAFE_INTEGER possible outputs, so the remainder picks one and adding 1 skips 0.
+// Each output is hit by about 2^11 inputs, so some outputs are only 1/2048 more likely than others.
+fn hash_safe_int(hash: u64) -> u64 {
+    hash % MAX_SAFE_INTEGER + 1
+}
+
+impl<T: ?Sized> SafeIntHashOf<T> {
+    fn from_u64(hash: u64) -> Self {
+        let hash = hash_safe_int(hash);
+
+        Self {
+            hash: unsafe { NonZeroU64::new_unchecked(hash) },
+            _marker: PhantomData,
+        }
+    }
+}
+
+default_hasher_from!(SafeIntHashOf);
+
+impl<T: ?Sized> From<SafeIntHashOf<T>> for u64 {
+    #[inline]
+    fn from(hash: SafeIntHashOf<T>) -> Self {
+        hash.to_u64()
+    }
+}
+
+impl<T: ?Sized> From<SafeIntHashOf<T>> for f64 {
+    #[inline]
+    fn from(hash: SafeIntHashOf<T>) -> Self {
+        hash.to_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    #[test]
+    fn in_safe_range() {
+        for &case in &[
+            0,
+            1,
+            MAX_SAFE_INTEGER - 1,
+            MAX_SAFE_INTEGER,
+            MAX_SAFE_INTEGER + 1,
+            u64::MAX,
+        ] {
+            let result = hash_safe_int(case);
+            assert!(result >= 1);
+            assert!(result <= MAX_SAFE_INTEGER);
+            assert_eq!(result as f64 as u64, result);
+        }
+    }
+
+    #[test]
+    fn option_is_niche_optimized() {
+        assert_eq!(size_of::<Option<SafeIntHashOf<String>>>(), size_of::<u64>());
+    }
+
+    #[test]
+    fn borrow_hash_eq() {
+        let x = SafeIntHashOf::<String>::from("test");
+        let y = SafeIntHashOf::<String>::from(&String::from("test"));
+        assert_eq!(x, y);
+        assert_eq!(u64::from(x) as f64, f64::from(y));
+    }
+}
@@ -0,0 +1,216 @@
+//! `Serialize` and `Deserialize` for `FloatHashOf` and `NullableFloatHash`, as a number.
+//!
+//! For formats which do not keep every bit of an f64, use one of the modules here
+//! with `#[serde(with = "...")]` instead, eg: `#[serde(with = "float_hash_of::serde::hex")]`
+
+use crate::{FloatHashOf, NullableFloatHash};
+use ::serde::de::{self, Deserialize, Deserializer, Visitor};
+use ::serde::ser::{Serialize, Serializer};
+use std::convert::TryFrom;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+
+impl<T: ?Sized, S: BuildHasher> Serialize for FloatHashOf<T, S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.serialize_f64(self.into_inner())
+    }
+}
+
+struct NumberVisitor<T: ?Sized, S>(PhantomData<fn() -> (*const T, S)>);
+
+impl<'de, T: ?Sized, S: BuildHasher> Visitor<'de> for NumberVisitor<T, S> {
+    type Value = FloatHashOf<T, S>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a float hash")
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+        FloatHashOf::try_from(value).map_err(E::custom)
+    }
+
+    // Large whole numbers, eg: 1e20, are written by JavaScript without a decimal point,
+    // and so arrive here. Each is the exact value of the float, so the conversion is exact too.
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        self.visit_f64(value as f64)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        self.visit_f64(value as f64)
+    }
+}
+
+impl<'de, T: ?Sized, S: BuildHasher> Deserialize<'de> for FloatHashOf<T, S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_f64(NumberVisitor(PhantomData))
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> Serialize for NullableFloatHash<T, S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        serializer.serialize_f64(self.into_inner())
+    }
+}
+
+struct NullableVisitor<T: ?Sized, S>(PhantomData<fn() -> (*const T, S)>);
+
+impl<'de, T: ?Sized, S: BuildHasher> Visitor<'de> for NullableVisitor<T, S> {
+    type Value = NullableFloatHash<T, S>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a float hash, 0 or null")
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Self::Value, E> {
+        NullableFloatHash::try_from(value).map_err(E::custom)
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        self.visit_f64(value as f64)
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        self.visit_f64(value as f64)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(NullableFloatHash::NULL)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(NullableFloatHash::NULL)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_f64(self)
+    }
+}
+
+impl<'de, T: ?Sized, S: BuildHasher> Deserialize<'de> for NullableFloatHash<T, S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // JSON.stringify writes holes in sparse arrays as null, so text formats may have it
+        // in place of 0. Binary formats could not tell an option from the number we write.
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_option(NullableVisitor(PhantomData))
+        } else {
+            deserializer.deserialize_f64(NullableVisitor(PhantomData))
+        }
+    }
+}
+
+/// A `FloatHashOf` as a string of 16 hex digits. See `TextFormat::Hex`
+pub mod hex {
+    use crate::{FloatHashOf, TextFormat};
+    use ::serde::de::{Deserialize, Deserializer, Error};
+    use ::serde::ser::Serializer;
+    use std::borrow::Cow;
+    use std::hash::BuildHasher;
+
+    pub fn serialize<T: ?Sized, S: BuildHasher, Ser: Serializer>(
+        hash: &FloatHashOf<T, S>,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error> {
+        serializer.collect_str(&hash.display(TextFormat::Hex))
+    }
+
+    pub fn deserialize<'de, T: ?Sized, S: BuildHasher, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<FloatHashOf<T, S>, D::Error> {
+        let text = Cow::<str>::deserialize(deserializer)?;
+        FloatHashOf::parse_as(&text, TextFormat::Hex).map_err(D::Error::custom)
+    }
+}
+
+/// A `FloatHashOf` as the u64 bits of the float
+pub mod bits {
+    use crate::FloatHashOf;
+    use ::serde::de::{Deserialize, Deserializer, Error};
+    use ::serde::ser::Serializer;
+    use std::convert::TryFrom;
+    use std::hash::BuildHasher;
+
+    pub fn serialize<T: ?Sized, S: BuildHasher, Ser: Serializer>(
+        hash: &FloatHashOf<T, S>,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error> {
+        serializer.serialize_u64(hash.into_inner().to_bits())
+    }
+
+    pub fn deserialize<'de, T: ?Sized, S: BuildHasher, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<FloatHashOf<T, S>, D::Error> {
+        let bits = u64::deserialize(deserializer)?;
+        FloatHashOf::try_from(bits).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FloatHashOf, NullableFloatHash};
+    use ::serde::{Deserialize, Serialize};
+    use std::convert::TryFrom;
+
+    type H = FloatHashOf<String>;
+
+    #[test]
+    fn number() {
+        let hash = H::try_from(-1.5).unwrap();
+        assert_eq!(serde_json::to_string(&hash).unwrap(), "-1.5");
+        assert_eq!(serde_json::from_str::<H>("-1.5").unwrap(), hash);
+
+        // Written by JavaScript as an integer
+        let whole = H::try_from(1e20).unwrap();
+        assert_eq!(
+            serde_json::from_str::<H>("100000000000000000000").unwrap(),
+            whole
+        );
+        let negative = H::try_from(-4.0).unwrap();
+        assert_eq!(serde_json::from_str::<H>("-4").unwrap(), negative);
+    }
+
+    #[test]
+    fn validates() {
+        let error = serde_json::from_str::<H>("0").unwrap_err();
+        assert!(error.to_string().starts_with("+-0 is never a float hash"));
+        assert!(serde_json::from_str::<H>("1e300").is_err());
+        assert!(serde_json::from_str::<H>("\"1.5\"").is_err());
+    }
+
+    #[test]
+    fn nullable() {
+        type N = NullableFloatHash<String>;
+        let hash = H::try_from(-1.5).unwrap();
+        let values = vec![N::from(hash), N::NULL];
+        assert_eq!(serde_json::to_string(&values).unwrap(), "[-1.5,0.0]");
+        assert_eq!(
+            serde_json::from_str::<Vec<N>>("[-1.5,0,-0.0,null]").unwrap(),
+            [N::from(hash), N::NULL, N::NULL, N::NULL]
+        );
+        assert!(serde_json::from_str::<N>("1e300").is_err());
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct Forms {
+        #[serde(with = "crate::serde::hex")]
+        hex: H,
+        #[serde(with = "crate::serde::bits")]
+        bits: H,
+    }
+
+    #[test]
+    fn with_modules() {
+        let hash = H::try_from(1.5).unwrap();
+        let forms = Forms {
+            hex: hash,
+            bits: hash,
+        };
+        let json = serde_json::to_string(&forms).unwrap();
+        assert_eq!(
+            json,
+            r#"{"hex":"3ff8000000000000","bits":4609434218613702656}"#
+        );
+        assert_eq!(serde_json::from_str::<Forms>(&json).unwrap(), forms);
+        assert!(serde_json::from_str::<Forms>(r#"{"hex":"0000000000000000","bits":1}"#).is_err());
+    }
+}
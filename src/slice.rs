@@ -0,0 +1,114 @@
+use crate::{validate_63_bits, FloatHashOf, InvalidFloatHash};
+use std::error::Error;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::slice;
+
+// These casts rely on FloatHashOf being #[repr(transparent)] over NonZeroU64, which has the
+// same size and alignment as f64. Every FloatHashOf is a valid f64, but not the other way around.
+impl<T: ?Sized, S: BuildHasher> FloatHashOf<T, S> {
+    /// Views hashes as the numbers JavaScript sees, eg: to copy into a `Float64Array` in one go
+    pub fn as_f64_slice(hashes: &[Self]) -> &[f64] {
+        // Safety: See above
+        unsafe { slice::from_raw_parts(hashes.as_ptr() as *const f64, hashes.len()) }
+    }
+
+    /// Views numbers as hashes without copying, after checking each is a float hash
+    pub fn try_from_f64_slice(values: &[f64]) -> Result<&[Self], InvalidFloatHashAt> {
+        for (index, value) in values.iter().enumerate() {
+            if let Err(reason) = validate_63_bits(value.to_bits()) {
+                return Err(InvalidFloatHashAt { index, reason });
+            }
+        }
+        // Safety: See above. Every value was just validated
+        Ok(unsafe { slice::from_raw_parts(values.as_ptr() as *const Self, values.len()) })
+    }
+}
+
+/// The first value in a slice which is not a float hash
+#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+pub struct InvalidFloatHashAt {
+    pub index: usize,
+    pub reason: InvalidFloatHash,
+}
+
+impl fmt::Display for InvalidFloatHashAt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "value at index {}: {}", self.index, self.reason)
+    }
+}
+
+impl Error for InvalidFloatHashAt {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.reason)
+    }
+}
+
+#[cfg(feature = "bytemuck")]
+mod bytemuck_impls {
+    use super::*;
+    use bytemuck::{CheckedBitPattern, NoUninit};
+
+    // Safety: A single NonZeroU64 with no padding
+    unsafe impl<T, S> NoUninit for FloatHashOf<T, S>
+    where
+        T: ?Sized + 'static,
+        S: BuildHasher + 'static,
+    {
+    }
+
+    // Safety: The bits are checked by the same rules as try_from_f64_slice
+    unsafe impl<T, S> CheckedBitPattern for FloatHashOf<T, S>
+    where
+        T: ?Sized + 'static,
+        S: BuildHasher + 'static,
+    {
+        type Bits = u64;
+
+        fn is_valid_bit_pattern(bits: &u64) -> bool {
+            validate_63_bits(*bits).is_ok()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_without_copying() {
+        let hashes: Vec<FloatHashOf<str>> = ["a", "b", "c"]
+            .iter()
+            .map(|&text| FloatHashOf::from(text))
+            .collect();
+        let values = FloatHashOf::as_f64_slice(&hashes);
+        assert_eq!(values.as_ptr() as usize, hashes.as_ptr() as usize);
+        assert_eq!(values[1], hashes[1].into_inner());
+
+        let back = FloatHashOf::<str>::try_from_f64_slice(values).unwrap();
+        assert_eq!(back, &hashes[..]);
+    }
+
+    #[test]
+    fn reports_first_invalid() {
+        let good = FloatHashOf::<str>::from("a").into_inner();
+        let err = FloatHashOf::<str>::try_from_f64_slice(&[good, good, -0., f64::NAN]).unwrap_err();
+        assert_eq!(
+            err,
+            InvalidFloatHashAt {
+                index: 2,
+                reason: InvalidFloatHash::Zero
+            }
+        );
+    }
+
+    #[cfg(feature = "bytemuck")]
+    #[test]
+    fn bytemuck_casts() {
+        let hashes = [FloatHashOf::<str>::from("a"), FloatHashOf::from("b")];
+        let bytes: &[u8] = bytemuck::cast_slice(&hashes);
+        let back: &[FloatHashOf<str>] = bytemuck::checked::cast_slice(bytes);
+        assert_eq!(back, &hashes);
+        assert!(bytemuck::checked::try_cast::<u64, FloatHashOf<str>>(0).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use crate::{birthday_probability, default_hasher_from, tag_only_traits};
+use std::marker::PhantomData;
+use std::num::NonZeroI32;
+
+/// The number of distinct values a `SmiHashOf` can take: every 31 bit signed integer except 0
+const SMI_OUTPUTS: u32 = (1 << 31) - 1;
+
+/// Takes a 64 bit hash, and reduces it to a non-zero 31 bit signed integer.
+/// V8 stores these unboxed as "small integers", which is the fastest representation for lookups,
+/// at the cost of far more collisions. See `collision_probability`.
+pub struct SmiHashOf<T: ?Sized> {
+    // Never 0, so that Option<SmiHashOf> is no larger
+    hash: NonZeroI32,
+    _marker: PhantomData<fn() -> *const T>,
+}
+
+tag_only_traits!(SmiHashOf<T>);
+
+impl<T: ?Sized> SmiHashOf<T> {
+    /// In `-2^30..2^30`, excluding 0
+    #[inline]
+    pub fn to_i32(self) -> i32 {
+        self.hash.get()
+    }
+
+    #[inline]
+    pub fn to_f64(self) -> f64 {
+        f64::from(self.hash.get())
+    }
+
+    /// As `FloatHashOf::collision_probability`, but with only 2^31 - 1 possible hashes,
+    /// so collisions are likely from around 50,000 keys
+    pub fn collision_probability(keys: u64) -> f64 {
+        birthday_probability(f64::from(SMI_OUTPUTS), keys)
+    }
+}
+
+// Pick one of the SMI_OUTPUTS values with a remainder, then send the lower half
+// to -2^30..=-1 and the upper half to 1..2^30, skipping 0.
+fn hash_smi(hash: u64) -> i32 {
+    const HALF: i64 = 1 << 30;
+
+    let n = (hash % u64::from(SMI_OUTPUTS)) as i64;
+    let smi = if n < HALF { -n - 1 } else { n - HALF + 1 };
+    smi as i32
+}
+
+impl<T: ?Sized> SmiHashOf<T> {
+    fn from_u64(hash: u64) -> Self {
+        let hash = hash_smi(hash);
+
+        Self {
+            hash: unsafe { NonZeroI32::new_unchecked(hash) },
+            _marker: PhantomData,
+        }
+    }
+}
+
+default_hasher_from!(SmiHashOf);
+
+impl<T: ?Sized> From<SmiHashOf<T>> for i32 {
+    #[inline]
+    fn from(hash: SmiHashOf<T>) -> Self {
+        hash.to_i32()
+    }
+}
+
+impl<T: ?Sized> From<SmiHashOf<T>> for f64 {
+    #[inline]
+    fn from(hash: SmiHashOf<T>) -> Self {
+        hash.to_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+
+    #[test]
+    fn in_smi_range() {
+        let min = -(1 << 30);
+        let max = (1 << 30) - 1;
+        assert_eq!(hash_smi(0), -1);
+        assert_eq!(hash_smi((1 << 30) - 1), min);
+        assert_eq!(hash_smi(1 << 30), 1);
+        assert_eq!(hash_smi(u64::from(SMI_OUTPUTS) - 1), max);
+        assert_eq!(hash_smi(u64::from(SMI_OUTPUTS)), -1);
+        for &case in &[u64::MAX, u64::MAX - 1, 1 << 63] {
+            let result = hash_smi(case);
+            assert!(result != 0 && result >= min && result <= max);
+        }
+    }
+
+    #[test]
+    fn option_is_niche_optimized() {
+        assert_eq!(size_of::<Option<SmiHashOf<String>>>(), size_of::<i32>());
+    }
+
+    #[test]
+    fn collision_probability_is_birthday_bound() {
+        assert_eq!(SmiHashOf::<String>::collision_probability(1), 0.);
+        // About 50% at the square root of the number of outputs
+        let half = SmiHashOf::<String>::collision_probability(54_562);
+        assert!((half - 0.5).abs() < 0.001);
+    }
+}
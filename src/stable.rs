@@ -0,0 +1,303 @@
+use crate::portable::portable_integer_writes;
+use crate::{FloatHashOf, PerItem};
+use std::error::Error;
+use std::fmt;
+use std::hash::{BuildHasherDefault, Hasher};
+
+// Arbitrary, but fixed forever. Changing these changes every stable hash.
+const K0: u64 = u64::from_le_bytes(*b"float-ha");
+const K1: u64 = u64::from_le_bytes(*b"sh-of-v1");
+
+/// SipHash with `C` compression rounds and `D` finalization rounds, over a byte stream.
+/// Writes may be split anywhere without changing the result.
+#[derive(Clone, Debug)]
+struct Sip<const C: usize, const D: usize> {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    // Bytes which have not yet made a full word, in the low `ntail` bytes
+    tail: u64,
+    ntail: usize,
+    length: usize,
+}
+
+impl<const C: usize, const D: usize> Sip<C, D> {
+    fn new_with_keys(k0: u64, k1: u64) -> Self {
+        Self {
+            v0: k0 ^ 0x736f_6d65_7073_6575,
+            v1: k1 ^ 0x646f_7261_6e64_6f6d,
+            v2: k0 ^ 0x6c79_6765_6e65_7261,
+            v3: k1 ^ 0x7465_6462_7974_6573,
+            tail: 0,
+            ntail: 0,
+            length: 0,
+        }
+    }
+
+    #[inline]
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13);
+        self.v1 ^= self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16);
+        self.v3 ^= self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21);
+        self.v3 ^= self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17);
+        self.v1 ^= self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    #[inline]
+    fn compress(&mut self, m: u64) {
+        self.v3 ^= m;
+        for _ in 0..C {
+            self.round();
+        }
+        self.v0 ^= m;
+    }
+
+    fn write(&mut self, mut bytes: &[u8]) {
+        self.length = self.length.wrapping_add(bytes.len());
+
+        // Top up the tail first
+        while self.ntail != 0 && !bytes.is_empty() {
+            self.tail |= u64::from(bytes[0]) << (8 * self.ntail);
+            self.ntail = (self.ntail + 1) % 8;
+            bytes = &bytes[1..];
+            if self.ntail == 0 {
+                self.compress(self.tail);
+                self.tail = 0;
+            }
+        }
+
+        let mut words = bytes.chunks_exact(8);
+        for word in &mut words {
+            let mut m = [0; 8];
+            m.copy_from_slice(word);
+            self.compress(u64::from_le_bytes(m));
+        }
+
+        // Either bytes were left after the top up, and so the tail is empty,
+        // or there were none left and the tail must be kept as it is
+        let remainder = words.remainder();
+        if !remainder.is_empty() {
+            for (i, &byte) in remainder.iter().enumerate() {
+                self.tail |= u64::from(byte) << (8 * i);
+            }
+            self.ntail = remainder.len();
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut state = self.clone();
+        let b = ((self.length as u64 & 0xff) << 56) | self.tail;
+        state.compress(b);
+        state.v2 ^= 0xff;
+        for _ in 0..D {
+            state.round();
+        }
+        state.v0 ^ state.v1 ^ state.v2 ^ state.v3
+    }
+}
+
+/// A hasher whose output is fixed by this crate, rather than by the Rust release.
+///
+/// The algorithm is SipHash-1-3 with keys taken from the little-endian bytes of `"float-ha"` and `"sh-of-v1"`.
+/// Integers are fed to it as `PortableHasher` does, so the output does not depend on the target either.
+///
+/// What is fed to the hasher is still decided by each type's `Hash` implementation,
+/// which Rust could change. `verify_stable_hashing` detects that.
+#[derive(Clone, Debug)]
+pub struct StableHasher(Sip<1, 3>);
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        StableHasher(Sip::new_with_keys(K0, K1))
+    }
+}
+
+impl Hasher for StableHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.0.write(bytes)
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0.finish()
+    }
+
+    portable_integer_writes!();
+}
+
+pub type StableBuildHasher = BuildHasherDefault<StableHasher>;
+
+/// A `FloatHashOf` which is safe to persist. See `StableHasher`.
+///
+/// Sequences of integers wider than a byte, eg: `Vec<u32>`, `Vec<u64>` or `[usize]`, are the exception:
+/// Rust hashes them as one write of their memory, so their hashes differ on big-endian targets,
+/// and for `usize`/`isize` on 32 bit targets. Wrap them in `PerItem` to hash each integer as above:
+///
+/// ```
+/// use float_hash_of::{PerItem, StableFloatHashOf};
+///
+/// let ids = StableFloatHashOf::<PerItem<Vec<u32>>>::from(&PerItem(vec![1, 2, 3]));
+/// assert_eq!(ids.into_inner(), 4.974961209214983e60);
+/// ```
+pub type StableFloatHashOf<T> = FloatHashOf<T, StableBuildHasher>;
+
+/// Strings, and the value of `StableFloatHashOf<String>` for each, which must never change.
+pub const STABLE_TEST_VECTORS: &[(&str, f64)] = &[
+    ("", -1.204161866627309e-123),
+    ("a", -593745437277.4401),
+    ("abc", 1.6864274440607716e-152),
+    ("hello world", -2.943468307799236e-72),
+    ("float-hash-of", -1.2119564935031145e-47),
+    (
+        "The quick brown fox jumps over the lazy dog",
+        2.0692513662916273e-8,
+    ),
+    ("ünïcödé", -6.536088505458339e38),
+    ("😀", -1.2924285927358677e-121),
+    ("0123456789abcdef", 8.860830888258102e-70),
+];
+
+/// The source of a value, a function hashing it, and the hash it must have
+pub type StableValueTestVector = (&'static str, fn() -> f64, f64);
+
+/// Values of other types, as Rust source, with a function computing their `StableFloatHashOf`
+/// and the value it must return. These catch changes to how Rust feeds integers, tuples,
+/// the lengths of sequences and enum discriminants to the hasher, which strings alone would not.
+///
+/// Each was checked against a separate SipHash-1-3 over the bytes `StableHasher` should see:
+/// integers little-endian, lengths and discriminants as 8 byte integers, strings followed by 0xff.
+pub const STABLE_VALUE_TEST_VECTORS: &[StableValueTestVector] = &[
+    (
+        "42u64",
+        || StableFloatHashOf::<u64>::from(&42u64).into_inner(),
+        -1.9507147085248993e65,
+    ),
+    (
+        "(String::from(\"abc\"), 7u32)",
+        || StableFloatHashOf::<(String, u32)>::from(&(String::from("abc"), 7u32)).into_inner(),
+        -1.9248713118690467e90,
+    ),
+    (
+        "vec![String::from(\"a\"), String::from(\"bc\")]",
+        || {
+            StableFloatHashOf::<Vec<String>>::from(&vec![String::from("a"), String::from("bc")])
+                .into_inner()
+        },
+        -5.046680110106673e-36,
+    ),
+    (
+        "Some(7u32)",
+        || StableFloatHashOf::<Option<u32>>::from(&Some(7u32)).into_inner(),
+        -3.9120749992557315e105,
+    ),
+    (
+        "None::<u32>",
+        || StableFloatHashOf::<Option<u32>>::from(&None::<u32>).into_inner(),
+        -5.239897204970473e-48,
+    ),
+    (
+        "PerItem(vec![1u32, 2, 3])",
+        || StableFloatHashOf::<PerItem<Vec<u32>>>::from(&PerItem(vec![1u32, 2, 3])).into_inner(),
+        4.974961209214983e60,
+    ),
+];
+
+/// A test vector which this build hashed differently
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct StableHashMismatch {
+    pub input: &'static str,
+    pub expected: f64,
+    pub actual: f64,
+}
+
+impl fmt::Display for StableHashMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "stable hash of {:?} was {:?}, expected {:?}",
+            self.input, self.actual, self.expected
+        )
+    }
+}
+
+impl Error for StableHashMismatch {}
+
+/// Checks that this build reproduces every one of `STABLE_TEST_VECTORS` and `STABLE_VALUE_TEST_VECTORS`.
+/// Call this at startup before reading or writing persisted hashes.
+pub fn verify_stable_hashing() -> Result<(), StableHashMismatch> {
+    for &(input, expected) in STABLE_TEST_VECTORS {
+        let actual = StableFloatHashOf::<String>::from(input).into_inner();
+        if actual.to_bits() != expected.to_bits() {
+            return Err(StableHashMismatch {
+                input,
+                expected,
+                actual,
+            });
+        }
+    }
+    for &(input, hash, expected) in STABLE_VALUE_TEST_VECTORS {
+        let actual = hash();
+        if actual.to_bits() != expected.to_bits() {
+            return Err(StableHashMismatch {
+                input,
+                expected,
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify() {
+        verify_stable_hashing().unwrap();
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn sip_2_4_matches_std() {
+        let input: Vec<u8> = (0..64).collect();
+        for len in 0..input.len() {
+            let mut std = std::hash::SipHasher::new_with_keys(K0, K1);
+            std.write(&input[..len]);
+            let mut ours = Sip::<2, 4>::new_with_keys(K0, K1);
+            // Split the writes to exercise the tail
+            let (a, b) = input[..len].split_at(len / 3);
+            ours.write(a);
+            ours.write(b);
+            assert_eq!(ours.finish(), std.finish());
+
+            let mut ours = Sip::<2, 4>::new_with_keys(K0, K1);
+            for byte in input[..len].chunks(1) {
+                ours.write(byte);
+            }
+            assert_eq!(ours.finish(), std.finish());
+        }
+    }
+
+    #[test]
+    fn integers_are_little_endian() {
+        let mut a = StableHasher::default();
+        a.write_u32(0x0403_0201);
+        a.write_usize(5);
+        let mut b = StableHasher::default();
+        b.write(&[1, 2, 3, 4]);
+        b.write(&[5, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(a.finish(), b.finish());
+    }
+}
@@ -0,0 +1,309 @@
+use crate::{FloatHashOf, InvalidFloatHash};
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{self, Write};
+use std::hash::BuildHasher;
+use std::str::FromStr;
+
+/// The ways a `FloatHashOf` can be written as text. Each can be read back with `FloatHashOf::parse_as`
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash, Default)]
+pub enum TextFormat {
+    /// The shortest decimal which reads back as the same float, written the way JavaScript's
+    /// `String(number)` and `JSON.stringify` write it, eg: `-1.5e-7` or `123456789`.
+    /// This is what `Display` and `FromStr` use.
+    #[default]
+    Decimal,
+    /// The bits of the float as 16 lowercase hex digits. Also available through `{:x}` and `{:X}`
+    Hex,
+    /// The big-endian bytes of the float as 11 characters of URL-safe base64 without padding
+    Base64,
+}
+
+/// Why text could not be read as a `FloatHashOf`
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum ParseFloatHashError {
+    /// The text was not in the expected format
+    Syntax,
+    /// The text was a float, but not one this crate could have produced
+    Invalid(InvalidFloatHash),
+}
+
+impl fmt::Display for ParseFloatHashError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseFloatHashError::Syntax => f.write_str("invalid syntax for a float hash"),
+            ParseFloatHashError::Invalid(invalid) => invalid.fmt(f),
+        }
+    }
+}
+
+impl Error for ParseFloatHashError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseFloatHashError::Syntax => None,
+            ParseFloatHashError::Invalid(invalid) => Some(invalid),
+        }
+    }
+}
+
+impl From<InvalidFloatHash> for ParseFloatHashError {
+    fn from(invalid: InvalidFloatHash) -> Self {
+        ParseFloatHashError::Invalid(invalid)
+    }
+}
+
+/// Displays a `FloatHashOf` in the chosen `TextFormat`. See `FloatHashOf::display`
+pub struct Formatted<T: ?Sized, S: BuildHasher> {
+    hash: FloatHashOf<T, S>,
+    format: TextFormat,
+}
+
+impl<T: ?Sized, S: BuildHasher> fmt::Display for Formatted<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        pad(f, |text| match self.format {
+            TextFormat::Decimal => write_js_number(text, self.hash.into_inner()),
+            TextFormat::Hex => write!(text, "{:016x}", self.hash.into_inner().to_bits()),
+            TextFormat::Base64 => write_base64(text, self.hash.to_be_bytes()),
+        })
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> FloatHashOf<T, S> {
+    pub fn display(self, format: TextFormat) -> Formatted<T, S> {
+        Formatted { hash: self, format }
+    }
+
+    pub fn parse_as(text: &str, format: TextFormat) -> Result<Self, ParseFloatHashError> {
+        let bits = match format {
+            TextFormat::Decimal => {
+                let value: f64 = text.parse().map_err(|_| ParseFloatHashError::Syntax)?;
+                value.to_bits()
+            }
+            TextFormat::Hex => {
+                // from_str_radix would also accept a sign
+                if text.len() != 16 || !text.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return Err(ParseFloatHashError::Syntax);
+                }
+                u64::from_str_radix(text, 16).map_err(|_| ParseFloatHashError::Syntax)?
+            }
+            TextFormat::Base64 => u64::from_be_bytes(read_base64(text)?),
+        };
+        Ok(Self::try_from(bits)?)
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> fmt::Display for FloatHashOf<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        pad(f, |text| write_js_number(text, self.into_inner()))
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> fmt::LowerHex for FloatHashOf<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&format!("{:016x}", self.into_inner().to_bits()))
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> fmt::UpperHex for FloatHashOf<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(&format!("{:016X}", self.into_inner().to_bits()))
+    }
+}
+
+impl<T: ?Sized, S: BuildHasher> FromStr for FloatHashOf<T, S> {
+    type Err = ParseFloatHashError;
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse_as(text, TextFormat::Decimal)
+    }
+}
+
+// Writes the text to a String first, so that the width, fill and alignment of `f` apply to all of it
+fn pad(f: &mut fmt::Formatter<'_>, write: impl FnOnce(&mut String) -> fmt::Result) -> fmt::Result {
+    let mut text = String::new();
+    write(&mut text)?;
+    f.pad(&text)
+}
+
+// Number::toString from the ECMAScript spec, for finite non-zero values.
+fn write_js_number(f: &mut impl Write, value: f64) -> fmt::Result {
+    if value < 0. {
+        f.write_str("-")?;
+    }
+    let value = value.abs();
+
+    // Rust's {:e} gives the shortest round-trip number of digits, but when there are several
+    // candidates with that many digits it does not always pick the closest one, which the spec requires.
+    // Rounding the exact value to that many digits gives the closest, so prefer that if it round-trips.
+    let mut scientific = format!("{:e}", value);
+    let precision = scientific.find('e').unwrap() - scientific.find('.').map_or(1, |_| 2);
+    let closest = format!("{:.*e}", precision, value);
+    if closest.parse::<f64>() == Ok(value) {
+        scientific = closest;
+    }
+
+    let (mantissa, exponent) = scientific.split_at(scientific.find('e').unwrap());
+    let digits = mantissa.replace('.', "");
+    // As named in the spec: value = 0.digits * 10^n, with k digits
+    let k = digits.len() as i32;
+    let n = exponent[1..].parse::<i32>().unwrap() + 1;
+
+    if k <= n && n <= 21 {
+        f.write_str(&digits)?;
+        for _ in k..n {
+            f.write_str("0")?;
+        }
+        Ok(())
+    } else if 0 < n && n <= 21 {
+        let (whole, fraction) = digits.split_at(n as usize);
+        write!(f, "{}.{}", whole, fraction)
+    } else if -6 < n && n <= 0 {
+        f.write_str("0.")?;
+        for _ in n..0 {
+            f.write_str("0")?;
+        }
+        f.write_str(&digits)
+    } else {
+        let (first, rest) = digits.split_at(1);
+        f.write_str(first)?;
+        if !rest.is_empty() {
+            write!(f, ".{}", rest)?;
+        }
+        let e = n - 1;
+        write!(f, "e{}{}", if e < 0 { '-' } else { '+' }, e.abs())
+    }
+}
+
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+// 8 bytes is 64 bits, which is 10 whole characters of 6 bits and 4 bits in the 11th
+fn write_base64(f: &mut impl Write, bytes: [u8; 8]) -> fmt::Result {
+    let bits = u64::from_be_bytes(bytes);
+    let mut text = [0; 11];
+    for (i, c) in text.iter_mut().enumerate() {
+        // Shifting the value left by 2 pads the final character with zeros
+        let sextet = ((u128::from(bits) << 2) >> (6 * (10 - i))) & 0x3f;
+        *c = BASE64_URL[sextet as usize];
+    }
+    f.write_str(std::str::from_utf8(&text).unwrap())
+}
+
+fn read_base64(text: &str) -> Result<[u8; 8], ParseFloatHashError> {
+    if text.len() != 11 {
+        return Err(ParseFloatHashError::Syntax);
+    }
+    let mut padded: u128 = 0;
+    for c in text.bytes() {
+        let sextet = BASE64_URL
+            .iter()
+            .position(|&b| b == c)
+            .ok_or(ParseFloatHashError::Syntax)?;
+        padded = (padded << 6) | sextet as u128;
+    }
+    // Only one encoding of each value is accepted
+    if padded & 0b11 != 0 {
+        return Err(ParseFloatHashError::Syntax);
+    }
+    Ok(((padded >> 2) as u64).to_be_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type H = FloatHashOf<()>;
+
+    fn hash(value: f64) -> H {
+        H::try_from(value).unwrap()
+    }
+
+    // Each produced by String(value) in JavaScript
+    #[test]
+    fn decimal_matches_javascript() {
+        let cases = [
+            (1., "1"),
+            (-2.5, "-2.5"),
+            (123456789., "123456789"),
+            (1e20, "100000000000000000000"),
+            (1e21, "1e+21"),
+            (1.5e21, "1.5e+21"),
+            (0.1, "0.1"),
+            (1.5e-6, "0.0000015"),
+            (1e-7, "1e-7"),
+            (-1.2345e-7, "-1.2345e-7"),
+            (1e150, "1e+150"),
+            (0.3333333333333333, "0.3333333333333333"),
+            (7929297801672961., "7929297801672961"),
+            // Rust's shortest digits end in 3, which also round-trips but is further away
+            (-1568647509030419.2, "-1568647509030419.2"),
+        ];
+        for &(value, text) in &cases {
+            assert_eq!(hash(value).to_string(), text);
+            assert_eq!(text.parse::<H>(), Ok(hash(value)));
+        }
+    }
+
+    #[test]
+    fn padding() {
+        let h = hash(-2.5);
+        assert_eq!(format!("{:>6}", h), "  -2.5");
+        assert_eq!(format!("{:*<6}", h), "-2.5**");
+        assert_eq!(format!("{:>18x}", h), "  c004000000000000");
+        assert_eq!(format!("{:^20X}", h), "  C004000000000000  ");
+        assert_eq!(
+            format!("{:>13}", h.display(TextFormat::Base64)),
+            "  wAQAAAAAAAA"
+        );
+    }
+
+    #[test]
+    fn hex() {
+        let h = hash(1.5);
+        assert_eq!(format!("{:x}", h), "3ff8000000000000");
+        assert_eq!(format!("{:X}", hash(-2.)), "C000000000000000");
+        assert_eq!(h.display(TextFormat::Hex).to_string(), "3ff8000000000000");
+        assert_eq!(H::parse_as("3FF8000000000000", TextFormat::Hex), Ok(h));
+        assert_eq!(
+            H::parse_as("+ff8000000000000", TextFormat::Hex),
+            Err(ParseFloatHashError::Syntax)
+        );
+        assert_eq!(
+            H::parse_as("0000000000000000", TextFormat::Hex),
+            Err(ParseFloatHashError::Invalid(InvalidFloatHash::Zero))
+        );
+    }
+
+    #[test]
+    fn base64() {
+        let h = hash(1.5);
+        let text = h.display(TextFormat::Base64).to_string();
+        assert_eq!(text, "P_gAAAAAAAA");
+        assert_eq!(H::parse_as(&text, TextFormat::Base64), Ok(h));
+        assert_eq!(
+            H::parse_as("P_gAAAAAAAB", TextFormat::Base64),
+            Err(ParseFloatHashError::Syntax)
+        );
+        assert_eq!(
+            H::parse_as("P_gAAAAAAA=", TextFormat::Base64),
+            Err(ParseFloatHashError::Syntax)
+        );
+
+        for &case in &[1.5, -1.9999999999999998, 2.0692513662916273e-8] {
+            let h = hash(case);
+            let text = h.display(TextFormat::Base64).to_string();
+            assert_eq!(H::parse_as(&text, TextFormat::Base64), Ok(h));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_floats() {
+        assert_eq!(
+            "NaN".parse::<H>(),
+            Err(ParseFloatHashError::Invalid(InvalidFloatHash::NaN))
+        );
+        assert_eq!(
+            "1e300".parse::<H>(),
+            Err(ParseFloatHashError::Invalid(InvalidFloatHash::Exponent))
+        );
+        assert_eq!("abc".parse::<H>(), Err(ParseFloatHashError::Syntax));
+    }
+}
@@ -0,0 +1,120 @@
+use crate::FloatHashOf;
+use std::hash::{BuildHasher, Hasher};
+
+/// Hashing strings as JavaScript sees them.
+///
+/// The hasher is fed each UTF-16 code unit as 2 little-endian bytes, with no length or terminator,
+/// and the result goes through the same mapping as `hash_u64_to_f64`. Code units are taken as they are,
+/// so lone surrogates (WTF-16) hash as themselves rather than as U+FFFD.
+///
+/// This differs from the `From` impls, which hash the UTF-8 bytes of a `str`.
+/// With `StableBuildHasher` the whole definition is fixed, so JavaScript can compute the same values
+/// as `StableFloatHashOf::from_str_utf16`:
+///
+/// ```js
+/// const M = (1n << 64n) - 1n;
+/// const rotl = (x, b) => ((x << b) | (x >> (64n - b))) & M;
+/// // SipHash-1-3 with the keys "float-ha" and "sh-of-v1", over 2 little-endian bytes per code unit
+/// function floatHashUtf16(str) {
+///   let v0 = 0x61682d74616f6c66n ^ 0x736f6d6570736575n, v1 = 0x31762d666f2d6873n ^ 0x646f72616e646f6dn;
+///   let v2 = 0x61682d74616f6c66n ^ 0x6c7967656e657261n, v3 = 0x31762d666f2d6873n ^ 0x7465646279746573n;
+///   const round = () => {
+///     v0 = (v0 + v1) & M; v1 = rotl(v1, 13n) ^ v0; v0 = rotl(v0, 32n);
+///     v2 = (v2 + v3) & M; v3 = rotl(v3, 16n) ^ v2;
+///     v0 = (v0 + v3) & M; v3 = rotl(v3, 21n) ^ v0;
+///     v2 = (v2 + v1) & M; v1 = rotl(v1, 17n) ^ v2; v2 = rotl(v2, 32n);
+///   };
+///   const compress = (m) => { v3 ^= m; round(); v0 ^= m; };
+///   let m = 0n, n = 0;
+///   for (let i = 0; i < str.length; i++, n += 2) {
+///     m |= BigInt(str.charCodeAt(i)) << BigInt((n % 8) * 8);
+///     if (n % 8 === 6) { compress(m); m = 0n; }
+///   }
+///   compress(m | (BigInt(n & 0xff) << 56n));
+///   v2 ^= 0xffn; round(); round(); round();
+///   let h = v0 ^ v1 ^ v2 ^ v3;
+///   // The mapping of hash_u64_to_f64
+///   const top = h & 0x6000000000000000n;
+///   if (top === 0n) h |= 0x4000000000000000n;
+///   else if (top === 0x6000000000000000n) h ^= 0x4000000000000000n;
+///   const u = new BigUint64Array([h]);
+///   return new Float64Array(u.buffer)[0];
+/// }
+/// ```
+impl<T: ?Sized, S: BuildHasher> FloatHashOf<T, S> {
+    pub fn from_utf16_with(units: &[u16], build_hasher: &S) -> Self {
+        let mut hasher = build_hasher.build_hasher();
+        for unit in units {
+            hasher.write(&unit.to_le_bytes());
+        }
+        Self::from_u64(hasher.finish())
+    }
+
+    pub fn from_utf16(units: &[u16]) -> Self
+    where
+        S: Default,
+    {
+        Self::from_utf16_with(units, &S::default())
+    }
+
+    /// The same as `from_utf16` of `value.encode_utf16()`, without collecting it
+    pub fn from_str_utf16(value: &str) -> Self
+    where
+        S: Default,
+    {
+        let mut hasher = S::default().build_hasher();
+        for unit in value.encode_utf16() {
+            hasher.write(&unit.to_le_bytes());
+        }
+        Self::from_u64(hasher.finish())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn str_matches_code_units() {
+        let value = "héllo 😀";
+        let units: Vec<u16> = value.encode_utf16().collect();
+        assert_eq!(
+            FloatHashOf::<String>::from_str_utf16(value),
+            FloatHashOf::<String>::from_utf16(&units)
+        );
+    }
+
+    #[test]
+    fn lone_surrogates_are_kept() {
+        let lone = FloatHashOf::<String>::from_utf16(&[0xd800]);
+        let replaced = FloatHashOf::<String>::from_str_utf16("\u{fffd}");
+        assert_ne!(lone, replaced);
+    }
+
+    #[test]
+    fn same_as_javascript() {
+        // From floatHashUtf16 above
+        let vectors: &[(&str, f64)] = &[
+            ("", 2.000002972618396e28),
+            ("a", -6.157593557275166e24),
+            ("abc", -5.2508496080572945e82),
+            ("héllo 😀", -4.193692622551056e-126),
+            ("0123456789abcdef", 1.4280165275206942e32),
+        ];
+        for &(text, expected) in vectors {
+            let units: Vec<u16> = text.encode_utf16().collect();
+            assert_eq!(
+                StableFloatHashOf::<str>::from_str_utf16(text).into_inner(),
+                expected
+            );
+            assert_eq!(
+                StableFloatHashOf::<str>::from_utf16(&units).into_inner(),
+                expected
+            );
+        }
+        assert_eq!(
+            StableFloatHashOf::<str>::from_utf16(&[0xd800]).into_inner(),
+            -6.639945249655844e149
+        );
+    }
+}
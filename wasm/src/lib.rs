@@ -0,0 +1,92 @@
+//! C-ABI exports of the hash functions, for JavaScript which does not embed the Rust app.
+//! Build with `cargo build -p float-hash-of-wasm --target wasm32-unknown-unknown --release`.
+//!
+//! The module has no imports, so it can be used directly:
+//!
+//! ```js
+//! const { instance } = await WebAssembly.instantiate(bytes);
+//! const { memory, alloc, dealloc, hash_utf8 } = instance.exports;
+//!
+//! const utf8 = new TextEncoder().encode("some key");
+//! const ptr = alloc(utf8.length);
+//! new Uint8Array(memory.buffer, ptr, utf8.length).set(utf8);
+//! const hash = hash_utf8(ptr, utf8.length); // === FloatHashOf::<String>::from("some key")
+//! dealloc(ptr, utf8.length);
+//! ```
+//!
+//! The `float_hash_of_*` functions of `float_hash_of::ffi` are exported as well.
+
+use float_hash_of::ffi;
+
+/// Reserves `len` bytes for the caller to write input into. Free with `dealloc`
+#[no_mangle]
+pub extern "C" fn alloc(len: usize) -> *mut u8 {
+    let mut buffer = Vec::<u8>::with_capacity(len);
+    let ptr = buffer.as_mut_ptr();
+    std::mem::forget(buffer);
+    ptr
+}
+
+/// # Safety
+/// `ptr` and `len` must be exactly as passed to and returned from `alloc`
+#[no_mangle]
+pub unsafe extern "C" fn dealloc(ptr: *mut u8, len: usize) {
+    drop(Vec::from_raw_parts(ptr, 0, len));
+}
+
+/// `float_hash_of::ffi::float_hash_of_hash_utf8`
+///
+/// # Safety
+/// As there
+#[no_mangle]
+pub unsafe extern "C" fn hash_utf8(ptr: *const u8, len: usize) -> f64 {
+    ffi::float_hash_of_hash_utf8(ptr, len)
+}
+
+/// `float_hash_of::ffi::float_hash_of_hash_utf8_stable`
+///
+/// # Safety
+/// As there
+#[no_mangle]
+pub unsafe extern "C" fn hash_utf8_stable(ptr: *const u8, len: usize) -> f64 {
+    ffi::float_hash_of_hash_utf8_stable(ptr, len)
+}
+
+/// `float_hash_of::hash_u64_to_f64`. The hash is a BigInt on the JavaScript side
+#[no_mangle]
+pub extern "C" fn hash_u64_to_f64(hash: u64) -> f64 {
+    float_hash_of::hash_u64_to_f64(hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use float_hash_of::{FloatHashOf, StableFloatHashOf};
+    use std::ptr;
+
+    #[test]
+    fn exports_match_library() {
+        for text in &["", "a", "hello", "ü"] {
+            let len = text.len();
+            let ptr = alloc(len);
+            unsafe {
+                ptr.copy_from_nonoverlapping(text.as_ptr(), len);
+                assert_eq!(
+                    hash_utf8(ptr, len),
+                    FloatHashOf::<String>::from(*text).into_inner()
+                );
+                assert_eq!(
+                    hash_utf8_stable(ptr, len),
+                    StableFloatHashOf::<String>::from(*text).into_inner()
+                );
+                dealloc(ptr, len);
+            }
+        }
+        assert_eq!(
+            unsafe { hash_utf8_stable(ptr::null(), 0) },
+            StableFloatHashOf::<String>::from("").into_inner()
+        );
+        let invalid = [0xffu8];
+        assert_eq!(unsafe { hash_utf8(invalid.as_ptr(), 1) }, 0.);
+    }
+}